@@ -1,34 +1,112 @@
 use std::fmt::{Display, Formatter, Result};
 
 use crate::{
-    expression::{BinaryExpression, Expression, LiteralExpression, UnaryExpression},
-    statement::Statement,
+    expression::{
+        AssignExpression, BinaryExpression, CallExpression, ConditionalExpression, Expression,
+        GetExpression, InterpolationExpression, LiteralExpression, LogicalExpression,
+        SetExpression, SuperExpression, UnaryExpression,
+    },
+    statement::{ClassDeclaration, FunctionDeclaration, Statement, StatementKind},
+    token::Token,
 };
 
 impl Display for Statement {
     fn fmt(&self, f: &mut Formatter) -> Result {
-        match self {
-            Self::Expression(inner) => write!(f, "(expression {inner})"),
-            Self::Print(inner) => write!(f, "(print {inner})"),
-            Self::VariableDeclaration(identifier, initial_value) => {
+        match &self.kind {
+            StatementKind::Block(statements) => {
+                write!(f, "(block")?;
+                for statement in statements {
+                    write!(f, " {statement}")?;
+                }
+                write!(f, ")")
+            }
+            StatementKind::Class(declaration) => declaration.fmt(f),
+            StatementKind::Expression(inner) => write!(f, "(expression {inner})"),
+            StatementKind::Function(declaration) => declaration.fmt(f),
+            StatementKind::If(condition, then_branch, else_branch) => {
+                write!(f, "(if {condition} {then_branch}")?;
+                if let Some(else_branch) = else_branch {
+                    write!(f, " {else_branch}")?;
+                }
+                write!(f, ")")
+            }
+            StatementKind::Print(inner) => write!(f, "(print {inner})"),
+            StatementKind::Return(value) => {
+                write!(f, "(return")?;
+                if let Some(value) = value {
+                    write!(f, " {value}")?;
+                }
+                write!(f, ")")
+            }
+            StatementKind::VariableDeclaration(identifier, initial_value) => {
                 write!(f, "(var {identifier} (")?;
                 if let Some(initial_value) = initial_value {
                     write!(f, "{initial_value}")?;
                 }
                 write!(f, "))")
             }
+            StatementKind::While(condition, body) => write!(f, "(while {condition} {body})"),
+            StatementKind::Error => write!(f, "(error)"),
+        }
+    }
+}
+
+impl Display for ClassDeclaration {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        let Self {
+            name,
+            superclass,
+            methods,
+        } = self;
+        write!(f, "(class {name}")?;
+        if let Some(superclass) = superclass {
+            write!(f, " (< {superclass})")?;
+        }
+        for method in methods {
+            write!(f, " {method}")?;
+        }
+        write!(f, ")")
+    }
+}
+
+impl Display for FunctionDeclaration {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        let Self {
+            name,
+            parameters,
+            body,
+            ..
+        } = self;
+        let parameters: Vec<_> = parameters
+            .iter()
+            .map(|(parameter, _)| parameter.as_str())
+            .collect();
+        write!(f, "(fun {name} ({})", parameters.join(" "))?;
+        for statement in body {
+            write!(f, " {statement}")?;
         }
+        write!(f, ")")
     }
 }
 
 impl Display for Expression {
     fn fmt(&self, f: &mut Formatter) -> Result {
         let self_variant: &dyn Display = match self {
-            Self::Grouping(inner) => return write!(f, "(group {inner})"),
-            Self::VariableReference(identifier) => return write!(f, "(value_of {identifier})"),
+            Self::Grouping(inner, _) => return write!(f, "(group {inner})"),
+            Self::VariableReference(identifier, ..) => return write!(f, "(value_of {identifier})"),
+            Self::This(..) => return write!(f, "this"),
+            Self::Error(_) => return write!(f, "(error)"),
             Self::Literal(inner) => inner,
             Self::Binary(inner) => inner,
+            Self::Call(inner) => inner,
+            Self::Get(inner) => inner,
+            Self::Super(inner) => inner,
+            Self::Interpolation(inner) => inner,
+            Self::Set(inner) => inner,
+            Self::Logical(inner) => inner,
+            Self::Conditional(inner) => inner,
             Self::Unary(inner) => inner,
+            Self::Assign(inner) => inner,
         };
         self_variant.fmt(f)
     }
@@ -40,14 +118,104 @@ impl Display for BinaryExpression {
             left,
             operator,
             right,
+            ..
+        } = self;
+        write!(f, "({operator} {left} {right})")
+    }
+}
+
+impl Display for AssignExpression {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        let Self {
+            identifier, value, ..
+        } = self;
+        write!(f, "(= {identifier} {value})")
+    }
+}
+
+impl Display for CallExpression {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        let Self {
+            callee, arguments, ..
+        } = self;
+        write!(f, "(call {callee}")?;
+        for argument in arguments {
+            write!(f, " {argument}")?;
+        }
+        write!(f, ")")
+    }
+}
+
+impl Display for GetExpression {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        let Self { object, name, .. } = self;
+        write!(f, "(. {object} {name})")
+    }
+}
+
+impl Display for SetExpression {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        let Self {
+            object,
+            name,
+            value,
+            ..
+        } = self;
+        write!(f, "(= (. {object} {name}) {value})")
+    }
+}
+
+impl Display for SuperExpression {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        let Self { method, .. } = self;
+        write!(f, "(super {method})")
+    }
+}
+
+impl Display for InterpolationExpression {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        write!(f, "(interpolate")?;
+        for part in &self.parts {
+            // Quote the string segments, so their spaces can be seen.
+            match part {
+                Expression::Literal(LiteralExpression {
+                    value: Token::String(segment),
+                    ..
+                }) => write!(f, " {segment:?}")?,
+                part => write!(f, " {part}")?,
+            }
+        }
+        write!(f, ")")
+    }
+}
+
+impl Display for LogicalExpression {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        let Self {
+            left,
+            operator,
+            right,
+            ..
         } = self;
         write!(f, "({operator} {left} {right})")
     }
 }
 
+impl Display for ConditionalExpression {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        let Self {
+            condition,
+            then_branch,
+            else_branch,
+            ..
+        } = self;
+        write!(f, "(?: {condition} {then_branch} {else_branch})")
+    }
+}
+
 impl Display for LiteralExpression {
     fn fmt(&self, f: &mut Formatter) -> Result {
-        let Self { value } = self;
+        let Self { value, .. } = self;
         write!(f, "{value}")
     }
 }
@@ -57,6 +225,7 @@ impl Display for UnaryExpression {
         let Self {
             operator,
             expression,
+            ..
         } = self;
         write!(f, "({operator} {expression})")
     }
@@ -65,19 +234,25 @@ impl Display for UnaryExpression {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::token::Token;
+    use crate::token::Span;
 
     #[test]
     fn test_ast_pretty_printing() {
+        let span = Span::default();
         let expression = BinaryExpression::new(
-            Expression::Unary(box UnaryExpression::new(
+            Expression::Unary(Box::new(UnaryExpression::new(
                 Token::Minus,
-                Expression::Literal(LiteralExpression::new(Token::Number(123.0))),
-            )),
-            Token::Star,
-            Expression::Grouping(box Expression::Literal(LiteralExpression::new(
-                Token::Number(45.67),
+                Expression::Literal(LiteralExpression::new(Token::Number(123.0), span)),
+                span,
             ))),
+            Token::Star,
+            Expression::Grouping(
+                Box::new(Expression::Literal(LiteralExpression::new(
+                    Token::Number(45.67),
+                    span,
+                ))),
+                span,
+            ),
         );
         let expression = expression.to_string();
 
@@ -0,0 +1,297 @@
+//! Errors and warnings rendered along with the source code they point at.
+//!
+//! ```txt
+//! [Parser Error]: Unclosed delimiter, expected ')'
+//!  --> script.lox:1:13
+//!   |
+//! 1 | print (1 + 2;
+//!   |       - unclosed delimiter
+//!   |             ^
+//! ```
+
+use std::fmt::Write;
+
+use crate::{token::Span, utils::colors};
+
+/// Spaces shown for each tab of the source, so underlines stay aligned.
+const TAB_WIDTH: usize = 4;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Severity {
+    fn color(self) -> &'static str {
+        match self {
+            Self::Error => *colors::RED,
+            Self::Warning => *colors::YELLOW,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Error => "Error",
+            Self::Warning => "Warning",
+        }
+    }
+}
+
+/// A message about a span of the source code, with optional labels, notes and help.
+#[derive(Debug)]
+pub struct Diagnostic {
+    severity: Severity,
+    /// Step of the interpreter that reported it, like "Parser".
+    stage: &'static str,
+    message: String,
+    span: Span,
+    /// Written after the `^~~~` underline of `span`.
+    label: Option<String>,
+    /// Other spans worth pointing at, underlined with `---`.
+    secondary_labels: Vec<(Span, String)>,
+    notes: Vec<String>,
+    help: Vec<String>,
+}
+
+impl Diagnostic {
+    pub fn error(stage: &'static str, message: impl Into<String>, span: Span) -> Self {
+        Self::new(Severity::Error, stage, message.into(), span)
+    }
+
+    pub fn warning(stage: &'static str, message: impl Into<String>, span: Span) -> Self {
+        Self::new(Severity::Warning, stage, message.into(), span)
+    }
+
+    fn new(severity: Severity, stage: &'static str, message: String, span: Span) -> Self {
+        Self {
+            severity,
+            stage,
+            message,
+            span,
+            label: None,
+            secondary_labels: vec![],
+            notes: vec![],
+            help: vec![],
+        }
+    }
+
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    pub fn with_secondary_label(mut self, span: Span, label: impl Into<String>) -> Self {
+        self.secondary_labels.push((span, label.into()));
+        self
+    }
+
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.help.push(help.into());
+        self
+    }
+
+    /// Render it as text, `source_code` must be the code its spans point at.
+    ///
+    /// Spans over many lines only have their first line shown.
+    pub fn render(&self, file_name: &str, source_code: &str) -> String {
+        let (color, blue, bold) = (self.severity.color(), *colors::BLUE, *colors::STYLE_BOLD);
+        let (reset, style_reset) = (*colors::RESET, *colors::STYLE_RESET);
+
+        let mut underlines = vec![Underline {
+            span: self.span,
+            label: self.label.as_deref(),
+            is_primary: true,
+        }];
+        underlines.extend(self.secondary_labels.iter().map(|(span, label)| {
+            Underline {
+                span: *span,
+                label: Some(label),
+                is_primary: false,
+            }
+        }));
+        underlines.sort_by_key(|underline| (underline.span.start.offset, underline.is_primary));
+
+        let lines = underlines.iter().map(|underline| underline.span.start.line);
+        let gutter_width = lines.max().unwrap_or(1).to_string().len();
+        let gutter = " ".repeat(gutter_width);
+
+        let mut output = String::new();
+        let start = self.span.start;
+        let (stage, severity, message) = (self.stage, self.severity.name(), &self.message);
+
+        // Writing to a `String` can't fail.
+        let _ = writeln!(output, "{color}[{stage} {severity}]{reset}: {message}");
+        let _ = writeln!(output, "{gutter}{blue}-->{reset} {file_name}:{start}");
+        let _ = write!(output, "{gutter} {blue}|{reset}");
+
+        let mut previous_line = None;
+        for underline in &underlines {
+            let line_number = underline.span.start.line;
+            let (line, underline_start, underline_width) = underline.locate(source_code);
+
+            if previous_line != Some(line_number) {
+                if previous_line.is_some_and(|previous| previous + 1 < line_number) {
+                    let _ = write!(output, "\n{blue}...{reset}");
+                }
+                let _ = write!(
+                    output,
+                    "\n{blue}{line_number:>gutter_width$} |{reset} {line}"
+                );
+                previous_line = Some(line_number);
+            }
+
+            let (underline_color, marks) = match underline.is_primary {
+                true => (color, format!("^{}", "~".repeat(underline_width - 1))),
+                false => (blue, "-".repeat(underline_width)),
+            };
+            let padding = " ".repeat(underline_start);
+            let label = underline.label.unwrap_or_default();
+            let _ = write!(
+                output,
+                "\n{gutter} {blue}|{reset} {padding}{underline_color}{marks} {label}{reset}"
+            );
+        }
+
+        for (kind, texts) in [("note", &self.notes), ("help", &self.help)] {
+            for text in texts {
+                let _ = write!(
+                    output,
+                    "\n{gutter} {blue}={reset} {bold}{kind}{style_reset}: {text}"
+                );
+            }
+        }
+
+        // Labels may be empty, leaving trailing spaces behind.
+        output
+            .lines()
+            .map(str::trim_end)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+struct Underline<'a> {
+    span: Span,
+    label: Option<&'a str>,
+    is_primary: bool,
+}
+
+impl Underline<'_> {
+    /// The line where the span starts, with tabs expanded, and the column and width of the
+    /// underline, both in chars.
+    ///
+    /// The underline is at least one char wide, so empty spans, like the end of the input,
+    /// still get a `^`.
+    fn locate(&self, source_code: &str) -> (String, usize, usize) {
+        let start = self.span.start.offset.min(source_code.len());
+        let end = self.span.end.offset.clamp(start, source_code.len());
+
+        let line_start = source_code[..start]
+            .rfind('\n')
+            .map_or(0, |newline| newline + 1);
+        let line_end = source_code[start..]
+            .find('\n')
+            .map_or(source_code.len(), |newline| start + newline);
+        let line = source_code[line_start..line_end].trim_end_matches('\r');
+
+        let width = |text: &str| {
+            text.chars()
+                .map(|ch| if ch == '\t' { TAB_WIDTH } else { 1 })
+                .sum::<usize>()
+        };
+        let underline_start = width(&source_code[line_start..start]);
+        let underline_end = width(&source_code[line_start..end.min(line_end)]);
+
+        let line = line.replace('\t', &" ".repeat(TAB_WIDTH));
+        (
+            line,
+            underline_start,
+            (underline_end - underline_start).max(1),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::token::Position;
+
+    /// Remove the colors, which depend on the terminal the tests run in.
+    fn strip_colors(text: &str) -> String {
+        let mut output = String::new();
+        let mut chars = text.chars();
+
+        while let Some(ch) = chars.next() {
+            if ch == '\u{1b}' {
+                chars.by_ref().find(|&ch| ch == 'm');
+            } else {
+                output.push(ch);
+            }
+        }
+
+        output
+    }
+
+    fn span(line: usize, start_column: usize, end_column: usize, line_offset: usize) -> Span {
+        Span::new(
+            Position::new(line, start_column, line_offset + start_column - 1),
+            Position::new(line, end_column, line_offset + end_column - 1),
+        )
+    }
+
+    #[test]
+    fn test_rendering_with_labels_notes_and_help() {
+        let source_code = "var a = 1;\nprint (a +\tnil;\n";
+
+        let diagnostic =
+            Diagnostic::error("Runtime", "Operands must be numbers.", span(2, 8, 15, 11))
+                .with_label("this is nil")
+                .with_secondary_label(span(2, 7, 8, 11), "unclosed delimiter")
+                .with_note("'nil' is not a number")
+                .with_help("initialize it first");
+
+        assert_eq!(
+            strip_colors(&diagnostic.render("main.lox", source_code)),
+            "\
+[Runtime Error]: Operands must be numbers.
+ --> main.lox:2:8
+  |
+2 | print (a +    nil;
+  |       - unclosed delimiter
+  |        ^~~~~~~~~~ this is nil
+  = note: 'nil' is not a number
+  = help: initialize it first"
+        );
+    }
+
+    #[test]
+    fn test_rendering_empty_and_multiline_spans() {
+        let source_code = "print 1\n\n/* never\nclosed";
+
+        let end_of_line = span(1, 8, 8, 0);
+        let diagnostic = Diagnostic::warning("Lexer", "Something is off.", end_of_line)
+            .with_secondary_label(
+                Span::new(Position::new(3, 1, 9), Position::new(4, 7, 24)),
+                "",
+            );
+
+        assert_eq!(
+            strip_colors(&diagnostic.render("<stdin>", source_code)),
+            "\
+[Lexer Warning]: Something is off.
+ --> <stdin>:1:8
+  |
+1 | print 1
+  |        ^
+...
+3 | /* never
+  | --------"
+        );
+    }
+}
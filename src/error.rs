@@ -1,19 +1,99 @@
 use std::{fmt, io, ops::Not};
 
-use crate::{interpreter::RuntimeError, lexer::LexerError, parser::ParserError, utils::colors};
+use crate::{
+    diagnostic::Diagnostic, interpreter::RuntimeError, lexer::LexerError, parser::ParserError,
+    resolver::ResolverError, utils::colors,
+};
 
 pub type Result<T> = std::result::Result<T, self::Error>;
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
-    #[error("{}[Lexer Error]{}: {0}", *colors::RED, *colors::RESET)]
-    Lexer(#[from] LexerError),
-    #[error("{}[Parser Error]{}: {0}", *colors::RED, *colors::RESET)]
+    #[error("{0}")]
+    Lexer(#[from] LexerErrorReporter),
+    #[error("{0}")]
     Parser(#[from] ParserErrorReporter),
-    #[error("{}[Runtime Error]{}: {0}", *colors::RED, *colors::RESET)]
+    #[error("{}[Resolver Error]{}: [{}] {0}", *colors::RED, *colors::RESET, .0.span().start)]
+    Resolver(#[from] ResolverError),
+    #[error("{}[Runtime Error]{}: [{}] {0}", *colors::RED, *colors::RESET, .0.span().start)]
     Runtime(#[from] RuntimeError),
     #[error("{}[IO Error]{}: {0}", *colors::RED, *colors::RESET)]
     Io(#[from] io::Error),
+    /// Errors already rendered along with their source code, see `Error::in_source`.
+    #[error("{0}")]
+    Diagnostics(String),
+}
+
+impl Error {
+    /// Render the errors that point at the source code as diagnostics showing it, others, like
+    /// IO errors, are kept as they are.
+    pub fn in_source(self, file_name: &str, source_code: &str) -> Self {
+        let diagnostics: Vec<Diagnostic> = match &self {
+            Self::Lexer(reporter) => {
+                reporter
+                    .errors()
+                    .iter()
+                    .map(LexerError::to_diagnostic)
+                    .collect()
+            }
+            Self::Parser(reporter) => {
+                reporter
+                    .errors()
+                    .iter()
+                    .map(ParserError::to_diagnostic)
+                    .collect()
+            }
+            Self::Resolver(error) => vec![error.to_diagnostic()],
+            Self::Runtime(error) => vec![error.to_diagnostic()],
+            Self::Io(_) | Self::Diagnostics(_) => return self,
+        };
+
+        let rendered: Vec<String> = diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.render(file_name, source_code))
+            .collect();
+
+        Self::Diagnostics(rendered.join("\n\n"))
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct LexerErrorReporter {
+    lexer_errors: Vec<LexerError>,
+}
+
+impl std::error::Error for LexerErrorReporter {}
+
+impl LexerErrorReporter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.lexer_errors.is_empty().not()
+    }
+
+    pub fn add_lexer_error(&mut self, error: LexerError) {
+        self.lexer_errors.push(error);
+    }
+
+    pub fn errors(&self) -> &[LexerError] {
+        &self.lexer_errors
+    }
+}
+
+/// One error per line, each with its own prefix.
+impl fmt::Display for LexerErrorReporter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, error) in self.lexer_errors.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            let (red, reset, start) = (*colors::RED, *colors::RESET, error.span().start);
+            write!(f, "{red}[Lexer Error]{reset}: [{start}] {error}")?;
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, Default)]
@@ -35,21 +115,21 @@ impl ParserErrorReporter {
     pub fn add_parser_error(&mut self, error: ParserError) {
         self.parser_errors.push(error);
     }
+
+    pub fn errors(&self) -> &[ParserError] {
+        &self.parser_errors
+    }
 }
 
+/// One error per line, each with its own prefix.
 impl fmt::Display for ParserErrorReporter {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         for (i, error) in self.parser_errors.iter().enumerate() {
-            write!(f, "{error}",)?;
-
             if i > 0 {
-                write!(
-                    f,
-                    "\n{}[Parser error]{}: {error}.",
-                    *colors::RED,
-                    *colors::RESET
-                )?;
+                writeln!(f)?;
             }
+            let (red, reset, start) = (*colors::RED, *colors::RESET, error.span().start);
+            write!(f, "{red}[Parser Error]{reset}: [{start}] {error}")?;
         }
         Ok(())
     }
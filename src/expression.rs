@@ -1,12 +1,72 @@
-use crate::token::Token;
+use std::cell::Cell;
+
+use crate::{
+    symbol::Symbol,
+    token::{Span, Token},
+};
 
 #[derive(Debug)]
 pub enum Expression {
     Literal(LiteralExpression),
     Binary(Box<BinaryExpression>),
-    Grouping(Box<Expression>),
+    Call(Box<CallExpression>),
+    Get(Box<GetExpression>),
+    Set(Box<SetExpression>),
+    Logical(Box<LogicalExpression>),
+    Conditional(Box<ConditionalExpression>),
+    Grouping(Box<Expression>, Span),
     Unary(Box<UnaryExpression>),
-    VariableReference(String),
+    VariableReference(Symbol, ScopeDepth, Span),
+    Assign(Box<AssignExpression>),
+    This(ScopeDepth, Span),
+    Super(SuperExpression),
+    Interpolation(InterpolationExpression),
+    /// Placeholder for an expression that failed to parse, see `Parser::parse_with_errors`.
+    Error(Span),
+}
+
+impl Expression {
+    /// Reference to a variable, yet to be resolved.
+    pub fn variable_reference(identifier: Symbol, span: Span) -> Self {
+        Self::VariableReference(identifier, ScopeDepth::default(), span)
+    }
+
+    /// Region of the source code this expression was parsed from.
+    pub fn span(&self) -> Span {
+        match self {
+            Self::Literal(inner) => inner.span,
+            Self::Binary(inner) => inner.span,
+            Self::Call(inner) => inner.span,
+            Self::Get(inner) => inner.span,
+            Self::Set(inner) => inner.span,
+            Self::Logical(inner) => inner.span,
+            Self::Conditional(inner) => inner.span,
+            Self::Unary(inner) => inner.span,
+            Self::Assign(inner) => inner.span,
+            Self::Super(inner) => inner.span,
+            Self::Interpolation(inner) => inner.span,
+            Self::Grouping(_, span)
+            | Self::VariableReference(.., span)
+            | Self::This(_, span)
+            | Self::Error(span) => *span,
+        }
+    }
+}
+
+/// How many scopes away from the usage a variable was declared, filled in by the resolver.
+///
+/// Stays unresolved for global variables, which are looked up by name.
+#[derive(Debug, Default)]
+pub struct ScopeDepth(Cell<Option<usize>>);
+
+impl ScopeDepth {
+    pub fn get(&self) -> Option<usize> {
+        self.0.get()
+    }
+
+    pub fn set(&self, depth: usize) {
+        self.0.set(Some(depth));
+    }
 }
 
 #[derive(Debug)]
@@ -14,14 +74,124 @@ pub struct BinaryExpression {
     pub left: Expression,
     pub operator: Token,
     pub right: Expression,
+    pub span: Span,
 }
 
 impl BinaryExpression {
     pub fn new(left: Expression, operator: Token, right: Expression) -> Self {
+        let span = left.span().to(right.span());
+        Self {
+            left,
+            operator,
+            right,
+            span,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct CallExpression {
+    pub callee: Expression,
+    pub arguments: Vec<Expression>,
+    pub span: Span,
+}
+
+impl CallExpression {
+    pub fn new(callee: Expression, arguments: Vec<Expression>, span: Span) -> Self {
+        Self {
+            callee,
+            arguments,
+            span,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct GetExpression {
+    pub object: Expression,
+    pub name: Symbol,
+    pub span: Span,
+}
+
+impl GetExpression {
+    pub fn new(object: Expression, name: Symbol, span: Span) -> Self {
+        Self { object, name, span }
+    }
+}
+
+#[derive(Debug)]
+pub struct SetExpression {
+    pub object: Expression,
+    pub name: Symbol,
+    pub value: Expression,
+    pub span: Span,
+}
+
+impl SetExpression {
+    pub fn new(object: Expression, name: Symbol, value: Expression) -> Self {
+        let span = object.span().to(value.span());
+        Self {
+            object,
+            name,
+            value,
+            span,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct SuperExpression {
+    pub method: Symbol,
+    pub depth: ScopeDepth,
+    pub span: Span,
+}
+
+impl SuperExpression {
+    pub fn new(method: Symbol, span: Span) -> Self {
+        Self {
+            method,
+            depth: ScopeDepth::default(),
+            span,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct LogicalExpression {
+    pub left: Expression,
+    pub operator: Token,
+    pub right: Expression,
+    pub span: Span,
+}
+
+impl LogicalExpression {
+    pub fn new(left: Expression, operator: Token, right: Expression) -> Self {
+        let span = left.span().to(right.span());
         Self {
             left,
             operator,
             right,
+            span,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ConditionalExpression {
+    pub condition: Expression,
+    pub then_branch: Expression,
+    pub else_branch: Expression,
+    pub span: Span,
+}
+
+impl ConditionalExpression {
+    pub fn new(condition: Expression, then_branch: Expression, else_branch: Expression) -> Self {
+        let span = condition.span().to(else_branch.span());
+        Self {
+            condition,
+            then_branch,
+            else_branch,
+            span,
         }
     }
 }
@@ -29,11 +199,12 @@ impl BinaryExpression {
 #[derive(Debug)]
 pub struct LiteralExpression {
     pub value: Token,
+    pub span: Span,
 }
 
 impl LiteralExpression {
-    pub fn new(value: Token) -> Self {
-        Self { value }
+    pub fn new(value: Token, span: Span) -> Self {
+        Self { value, span }
     }
 }
 
@@ -41,13 +212,48 @@ impl LiteralExpression {
 pub struct UnaryExpression {
     pub operator: Token,
     pub expression: Expression,
+    pub span: Span,
 }
 
 impl UnaryExpression {
-    pub fn new(operator: Token, expression: Expression) -> Self {
+    pub fn new(operator: Token, expression: Expression, span: Span) -> Self {
         Self {
             operator,
             expression,
+            span,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct AssignExpression {
+    pub identifier: Symbol,
+    pub value: Expression,
+    pub depth: ScopeDepth,
+    pub span: Span,
+}
+
+impl AssignExpression {
+    pub fn new(identifier: Symbol, value: Expression, span: Span) -> Self {
+        Self {
+            identifier,
+            value,
+            depth: ScopeDepth::default(),
+            span,
         }
     }
 }
+
+/// A string with embedded expressions, `parts` alternate between string segments and the
+/// interpolated expressions.
+#[derive(Debug)]
+pub struct InterpolationExpression {
+    pub parts: Vec<Expression>,
+    pub span: Span,
+}
+
+impl InterpolationExpression {
+    pub fn new(parts: Vec<Expression>, span: Span) -> Self {
+        Self { parts, span }
+    }
+}
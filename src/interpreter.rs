@@ -1,26 +1,61 @@
-use std::{collections::HashMap, ops::Not};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fmt,
+    ops::{ControlFlow, Not},
+    rc::Rc,
+};
 
 use crate::{
-    expression::{BinaryExpression, Expression, LiteralExpression, UnaryExpression},
-    statement::Statement,
-    token::Token,
+    diagnostic::Diagnostic,
+    expression::{
+        AssignExpression, BinaryExpression, CallExpression, ConditionalExpression, Expression,
+        GetExpression, InterpolationExpression, LiteralExpression, LogicalExpression, ScopeDepth,
+        SetExpression, SuperExpression, UnaryExpression,
+    },
+    statement::{ClassDeclaration, FunctionDeclaration, Statement, StatementKind},
+    symbol::Symbol,
+    token::{Span, Token},
 };
 
 pub fn interpret_program(
     statements: Vec<Statement>,
-    environment: &mut Environment,
+    environment: &Environment,
 ) -> Result<(), RuntimeError> {
-    for statement in statements {
-        let expression = statement.evaluate(environment)?;
-        println!("evaluated: {expression:?}");
+    for statement in &statements {
+        if let ControlFlow::Break(_) = statement.execute(environment)? {
+            return Err(RuntimeError::ReturnOutsideFunction(statement.span));
+        }
     }
 
     Ok(())
 }
 
+/// Execute statements in order, stopping early if one of them returns.
+fn execute_statements(
+    statements: &[Statement],
+    environment: &Environment,
+) -> Result<ControlFlow<Value>, RuntimeError> {
+    for statement in statements {
+        if let ControlFlow::Break(value) = statement.execute(environment)? {
+            return Ok(ControlFlow::Break(value));
+        }
+    }
+
+    Ok(ControlFlow::Continue(()))
+}
+
+/// A lexical scope, linked to the scope that encloses it.
+///
+/// This is a cheap handle to a shared scope, so closures can keep the scope they were created in
+/// alive, and see every change made to it.
+#[derive(Default, Debug, Clone)]
+pub struct Environment(Rc<RefCell<Scope>>);
+
 #[derive(Default, Debug)]
-pub struct Environment {
-    variables: HashMap<String, Option<Value>>,
+struct Scope {
+    variables: HashMap<Symbol, Option<Value>>,
+    enclosing: Option<Environment>,
 }
 
 impl Environment {
@@ -28,21 +63,67 @@ impl Environment {
         Self::default()
     }
 
-    fn define(&mut self, identifier: String) {
-        self.variables.insert(identifier, None);
+    /// Create a new innermost scope, enclosed by the given one.
+    fn new_enclosed(enclosing: &Environment) -> Self {
+        Self(Rc::new(RefCell::new(Scope {
+            variables: HashMap::new(),
+            enclosing: Some(enclosing.clone()),
+        })))
     }
 
-    fn set_value(&mut self, identifier: String, value: Value) {
-        self.variables.insert(identifier, Some(value));
+    fn define(&self, identifier: Symbol) {
+        let mut scope = self.0.borrow_mut();
+        scope.variables.insert(identifier, None);
     }
 
-    fn get_value(&self, identifier: &str) -> Option<Option<Value>> {
-        self.variables.get(identifier).cloned()
+    fn set_value(&self, identifier: Symbol, value: Value) {
+        let mut scope = self.0.borrow_mut();
+        scope.variables.insert(identifier, Some(value));
+    }
+
+    /// Find the scope `depth` levels up the chain, or the global one if `depth` is `None`.
+    fn ancestor(&self, depth: Option<usize>) -> Environment {
+        let mut environment = self.clone();
+        let mut depth = depth.unwrap_or(usize::MAX);
+
+        while depth > 0 {
+            let Some(enclosing) = environment.0.borrow().enclosing.clone() else {
+                break;
+            };
+            environment = enclosing;
+            depth -= 1;
+        }
+
+        environment
+    }
+
+    /// Assign to an already declared variable of this scope.
+    fn assign(&self, identifier: Symbol, value: Value, span: Span) -> Result<(), RuntimeError> {
+        let mut scope = self.0.borrow_mut();
+
+        match scope.variables.get_mut(&identifier) {
+            Some(variable) => {
+                *variable = Some(value);
+                Ok(())
+            }
+            None => Err(RuntimeError::UndefinedVariable(identifier, span)),
+        }
+    }
+
+    /// Look up a variable of this scope.
+    fn get_value(&self, identifier: Symbol) -> Option<Option<Value>> {
+        let scope = self.0.borrow();
+        scope.variables.get(&identifier).cloned()
     }
 }
 
 pub trait Interpret {
-    fn evaluate(&self, environment: &mut Environment) -> Result<Value, RuntimeError>;
+    fn evaluate(&self, environment: &Environment) -> Result<Value, RuntimeError>;
+}
+
+/// Statements don't produce values, but a `return` breaks out with one.
+pub trait Execute {
+    fn execute(&self, environment: &Environment) -> Result<ControlFlow<Value>, RuntimeError>;
 }
 
 #[derive(PartialEq, Debug, Clone)]
@@ -50,6 +131,9 @@ pub enum Value {
     String(String),
     Number(f64),
     Bool(bool),
+    Function(Function),
+    Class(Rc<Class>),
+    Instance(Rc<Instance>),
     Nil,
 }
 
@@ -62,55 +146,378 @@ impl Value {
     }
 }
 
-impl Interpret for Statement {
-    fn evaluate(&self, environment: &mut Environment) -> Result<Value, RuntimeError> {
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            Self::Expression(inner) => inner.evaluate(environment),
-            Self::Print(inner) => {
-                print!("print ");
-                inner.evaluate(environment)
+            Self::String(inner) => inner.fmt(f),
+            Self::Number(inner) => inner.fmt(f),
+            Self::Bool(inner) => inner.fmt(f),
+            Self::Function(inner) => inner.fmt(f),
+            Self::Class(inner) => inner.fmt(f),
+            Self::Instance(inner) => inner.fmt(f),
+            Self::Nil => "nil".fmt(f),
+        }
+    }
+}
+
+/// Values that can be called with a list of arguments.
+trait Callable {
+    fn arity(&self) -> usize;
+    fn call(&self, arguments: Vec<Value>) -> Result<Value, RuntimeError>;
+}
+
+/// A user-defined function, together with the scope it was declared in.
+#[derive(Clone)]
+pub struct Function {
+    declaration: Rc<FunctionDeclaration>,
+    closure: Environment,
+    /// Initializers always return `this`, whatever the body returns.
+    is_initializer: bool,
+}
+
+impl Function {
+    fn new(declaration: Rc<FunctionDeclaration>, closure: Environment) -> Self {
+        Self {
+            declaration,
+            closure,
+            is_initializer: false,
+        }
+    }
+
+    /// Create a method, `this` is only available after binding it to an instance.
+    fn new_method(declaration: Rc<FunctionDeclaration>, closure: Environment) -> Self {
+        let is_initializer = declaration.name == Symbol::INIT;
+        Self {
+            declaration,
+            closure,
+            is_initializer,
+        }
+    }
+
+    /// Bind `this` to the given instance, in a scope between the closure and the body.
+    fn bind(&self, instance: Rc<Instance>) -> Function {
+        let environment = Environment::new_enclosed(&self.closure);
+        environment.set_value(Symbol::THIS, Value::Instance(instance));
+
+        Self {
+            closure: environment,
+            ..self.clone()
+        }
+    }
+}
+
+impl Callable for Function {
+    fn arity(&self) -> usize {
+        self.declaration.parameters.len()
+    }
+
+    fn call(&self, arguments: Vec<Value>) -> Result<Value, RuntimeError> {
+        // The function body sees the scope it was declared in, not the caller's.
+        let environment = Environment::new_enclosed(&self.closure);
+
+        for ((parameter, _), argument) in self.declaration.parameters.iter().zip(arguments) {
+            environment.set_value(*parameter, argument);
+        }
+
+        let return_value = match execute_statements(&self.declaration.body, &environment)? {
+            ControlFlow::Continue(()) => Value::Nil,
+            ControlFlow::Break(value) => value,
+        };
+
+        if self.is_initializer {
+            if let Some(Some(this)) = self.closure.get_value(Symbol::THIS) {
+                return Ok(this);
+            }
+        }
+
+        Ok(return_value)
+    }
+}
+
+impl PartialEq for Function {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.declaration, &other.declaration)
+            && Rc::ptr_eq(&self.closure.0, &other.closure.0)
+    }
+}
+
+impl fmt::Debug for Function {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl fmt::Display for Function {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "<fn {}>", self.declaration.name)
+    }
+}
+
+/// A user-defined class, calling it constructs a new instance.
+pub struct Class {
+    name: Symbol,
+    superclass: Option<Rc<Class>>,
+    methods: HashMap<Symbol, Function>,
+}
+
+impl Class {
+    /// Look up a method, walking up the superclass chain.
+    fn find_method(&self, name: Symbol) -> Option<&Function> {
+        match self.methods.get(&name) {
+            Some(method) => Some(method),
+            None => self.superclass.as_ref()?.find_method(name),
+        }
+    }
+}
+
+impl Callable for Rc<Class> {
+    fn arity(&self) -> usize {
+        self.find_method(Symbol::INIT).map_or(0, Callable::arity)
+    }
+
+    fn call(&self, arguments: Vec<Value>) -> Result<Value, RuntimeError> {
+        let instance = Rc::new(Instance::new(Rc::clone(self)));
+
+        if let Some(initializer) = self.find_method(Symbol::INIT) {
+            initializer.bind(Rc::clone(&instance)).call(arguments)?;
+        }
+
+        Ok(Value::Instance(instance))
+    }
+}
+
+/// Classes are only equal to themselves.
+impl PartialEq for Class {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
+
+impl fmt::Debug for Class {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl fmt::Display for Class {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.name.fmt(f)
+    }
+}
+
+pub struct Instance {
+    class: Rc<Class>,
+    fields: RefCell<HashMap<Symbol, Value>>,
+}
+
+impl Instance {
+    fn new(class: Rc<Class>) -> Self {
+        Self {
+            class,
+            fields: RefCell::default(),
+        }
+    }
+
+    /// Look up a field, or a method bound to this instance, fields shadow methods.
+    fn get(self: &Rc<Self>, name: Symbol, span: Span) -> Result<Value, RuntimeError> {
+        if let Some(value) = self.fields.borrow().get(&name) {
+            return Ok(value.clone());
+        }
+
+        match self.class.find_method(name) {
+            Some(method) => Ok(Value::Function(method.bind(Rc::clone(self)))),
+            None => Err(RuntimeError::UndefinedProperty(name, span)),
+        }
+    }
+
+    fn set(&self, name: Symbol, value: Value) {
+        self.fields.borrow_mut().insert(name, value);
+    }
+}
+
+/// Instances are only equal to themselves.
+impl PartialEq for Instance {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
+
+impl fmt::Debug for Instance {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl fmt::Display for Instance {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} instance", self.class)
+    }
+}
+
+impl Execute for Statement {
+    fn execute(&self, environment: &Environment) -> Result<ControlFlow<Value>, RuntimeError> {
+        match &self.kind {
+            StatementKind::Block(statements) => {
+                let environment = Environment::new_enclosed(environment);
+                execute_statements(statements, &environment)
+            }
+            StatementKind::Class(ClassDeclaration {
+                name,
+                superclass,
+                methods,
+            }) => {
+                let superclass = match superclass {
+                    Some(Expression::VariableReference(identifier, _, span))
+                        if identifier == name =>
+                    {
+                        return Err(RuntimeError::ClassInheritsFromItself(*name, *span));
+                    }
+                    Some(superclass) => {
+                        match superclass.evaluate(environment)? {
+                            Value::Class(superclass) => Some(superclass),
+                            value => {
+                                let span = superclass.span();
+                                return Err(RuntimeError::SuperclassMustBeAClass(value, span));
+                            }
+                        }
+                    }
+                    None => None,
+                };
+
+                // Methods of a subclass see `super` in a scope between them and the class.
+                let methods_environment = match &superclass {
+                    Some(superclass) => {
+                        let environment = Environment::new_enclosed(environment);
+                        environment.set_value(Symbol::SUPER, Value::Class(Rc::clone(superclass)));
+                        environment
+                    }
+                    None => environment.clone(),
+                };
+
+                let methods = methods
+                    .iter()
+                    .map(|method| {
+                        let function =
+                            Function::new_method(Rc::clone(method), methods_environment.clone());
+                        (method.name, function)
+                    })
+                    .collect();
+
+                let class = Class {
+                    name: *name,
+                    superclass,
+                    methods,
+                };
+                environment.set_value(*name, Value::Class(Rc::new(class)));
+                Ok(ControlFlow::Continue(()))
+            }
+            StatementKind::Expression(inner) => {
+                inner.evaluate(environment)?;
+                Ok(ControlFlow::Continue(()))
             }
-            Statement::VariableDeclaration(identifier, initial_value_expression) => {
+            StatementKind::Function(declaration) => {
+                let function = Function::new(Rc::clone(declaration), environment.clone());
+                environment.set_value(declaration.name, Value::Function(function));
+                Ok(ControlFlow::Continue(()))
+            }
+            StatementKind::If(condition, then_branch, else_branch) => {
+                if is_truthy(&condition.evaluate(environment)?) {
+                    then_branch.execute(environment)
+                } else if let Some(else_branch) = else_branch {
+                    else_branch.execute(environment)
+                } else {
+                    Ok(ControlFlow::Continue(()))
+                }
+            }
+            StatementKind::Print(inner) => {
+                println!("{}", inner.evaluate(environment)?);
+                Ok(ControlFlow::Continue(()))
+            }
+            StatementKind::Return(value) => {
+                let value = match value {
+                    Some(expression) => expression.evaluate(environment)?,
+                    None => Value::Nil,
+                };
+                Ok(ControlFlow::Break(value))
+            }
+            StatementKind::VariableDeclaration(identifier, initial_value_expression) => {
                 match initial_value_expression {
                     Some(expression) => {
                         let value = expression.evaluate(environment)?;
-                        environment.set_value(identifier.clone(), value);
+                        environment.set_value(*identifier, value);
                     }
                     None => {
-                        environment.define(identifier.clone());
+                        environment.define(*identifier);
                     }
                 };
 
-                Ok(Value::Nil)
+                Ok(ControlFlow::Continue(()))
             }
+            StatementKind::While(condition, body) => {
+                while is_truthy(&condition.evaluate(environment)?) {
+                    if let ControlFlow::Break(value) = body.execute(environment)? {
+                        return Ok(ControlFlow::Break(value));
+                    }
+                }
+
+                Ok(ControlFlow::Continue(()))
+            }
+            StatementKind::Error => unreachable!("programs with syntax errors aren't run"),
         }
     }
 }
 
 impl Interpret for Expression {
-    fn evaluate(&self, environment: &mut Environment) -> Result<Value, RuntimeError> {
+    fn evaluate(&self, environment: &Environment) -> Result<Value, RuntimeError> {
         let self_variant: &dyn Interpret = match self {
             Self::Literal(inner) => inner,
             Self::Binary(inner) => inner.as_ref(),
-            Self::Grouping(inner) => inner.as_ref(),
+            Self::Call(inner) => inner.as_ref(),
+            Self::Get(inner) => inner.as_ref(),
+            Self::Set(inner) => inner.as_ref(),
+            Self::Logical(inner) => inner.as_ref(),
+            Self::Conditional(inner) => inner.as_ref(),
+            Self::Grouping(inner, _) => inner.as_ref(),
             Self::Unary(inner) => inner.as_ref(),
-            Self::VariableReference(identifier) => {
-                return match environment.get_value(identifier) {
-                    Some(Some(value)) => Ok(value),
-                    Some(None) => Err(RuntimeError::UninitializedVariable(identifier.clone())),
-                    None => Err(RuntimeError::UndefinedVariable(identifier.clone())),
-                };
+            Self::Assign(inner) => inner.as_ref(),
+            Self::VariableReference(identifier, depth, span) => {
+                return look_up_variable(environment, *identifier, depth, *span);
+            }
+            Self::This(depth, span) => {
+                return look_up_variable(environment, Symbol::THIS, depth, *span)
+                    .map_err(|_| RuntimeError::ThisOutsideClass(*span));
             }
+            Self::Super(inner) => inner,
+            Self::Interpolation(inner) => inner,
+            Self::Error(_) => unreachable!("programs with syntax errors aren't run"),
         };
 
         Interpret::evaluate(self_variant, environment)
     }
 }
 
+impl Interpret for AssignExpression {
+    fn evaluate(&self, environment: &Environment) -> Result<Value, RuntimeError> {
+        let Self {
+            identifier,
+            value,
+            depth,
+            span,
+        } = self;
+
+        let value = value.evaluate(environment)?;
+        environment
+            .ancestor(depth.get())
+            .assign(*identifier, value.clone(), *span)?;
+
+        Ok(value)
+    }
+}
+
 impl Interpret for LiteralExpression {
-    fn evaluate(&self, _environment: &mut Environment) -> Result<Value, RuntimeError> {
+    fn evaluate(&self, _environment: &Environment) -> Result<Value, RuntimeError> {
         let value = match &self.value {
-            Token::String(inner) => Value::String(inner.to_owned()),
+            Token::String(inner) => Value::String(inner.as_str().to_owned()),
             Token::Number(inner) => Value::Number(*inner),
             Token::Bool(inner) => Value::Bool(*inner),
             Token::Nil => Value::Nil,
@@ -122,42 +529,43 @@ impl Interpret for LiteralExpression {
 }
 
 impl Interpret for BinaryExpression {
-    fn evaluate(&self, environment: &mut Environment) -> Result<Value, RuntimeError> {
+    fn evaluate(&self, environment: &Environment) -> Result<Value, RuntimeError> {
         let Self {
             left,
             operator,
             right,
+            span,
         } = self;
 
         let (lhs, rhs) = (left.evaluate(environment)?, right.evaluate(environment)?);
 
         let value = match operator {
             Token::Minus => {
-                check_number_operands(operator, &lhs, &rhs)?;
+                check_number_operands(operator, &lhs, &rhs, *span)?;
                 Value::Number(lhs.to_number() - rhs.to_number())
             }
             Token::Slash => {
-                check_number_operands(operator, &lhs, &rhs)?;
+                check_number_operands(operator, &lhs, &rhs, *span)?;
                 Value::Number(lhs.to_number() / rhs.to_number())
             }
             Token::Star => {
-                check_number_operands(operator, &lhs, &rhs)?;
+                check_number_operands(operator, &lhs, &rhs, *span)?;
                 Value::Number(lhs.to_number() * rhs.to_number())
             }
             Token::Greater => {
-                check_number_operands(operator, &lhs, &rhs)?;
+                check_number_operands(operator, &lhs, &rhs, *span)?;
                 Value::Bool(lhs.to_number() > rhs.to_number())
             }
             Token::GreaterEqual => {
-                check_number_operands(operator, &lhs, &rhs)?;
+                check_number_operands(operator, &lhs, &rhs, *span)?;
                 Value::Bool(lhs.to_number() >= rhs.to_number())
             }
             Token::Less => {
-                check_number_operands(operator, &lhs, &rhs)?;
+                check_number_operands(operator, &lhs, &rhs, *span)?;
                 Value::Bool(lhs.to_number() < rhs.to_number())
             }
             Token::LessEqual => {
-                check_number_operands(operator, &lhs, &rhs)?;
+                check_number_operands(operator, &lhs, &rhs, *span)?;
                 Value::Bool(lhs.to_number() <= rhs.to_number())
             }
             Token::BangEqual => Value::Bool(lhs.to_number() != rhs.to_number()),
@@ -178,22 +586,152 @@ impl Interpret for BinaryExpression {
     }
 }
 
+impl Interpret for CallExpression {
+    fn evaluate(&self, environment: &Environment) -> Result<Value, RuntimeError> {
+        let Self {
+            callee,
+            arguments,
+            span,
+        } = self;
+
+        let callee = callee.evaluate(environment)?;
+        let arguments = arguments
+            .iter()
+            .map(|argument| argument.evaluate(environment))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let callable: &dyn Callable = match &callee {
+            Value::Function(function) => function,
+            Value::Class(class) => class,
+            _ => return Err(RuntimeError::NotCallable(callee, *span)),
+        };
+
+        let (expected, found) = (callable.arity(), arguments.len());
+        if expected != found {
+            return Err(RuntimeError::ArityMismatch(expected, found, *span));
+        }
+
+        callable.call(arguments)
+    }
+}
+
+impl Interpret for SuperExpression {
+    fn evaluate(&self, environment: &Environment) -> Result<Value, RuntimeError> {
+        let Self {
+            method,
+            depth,
+            span,
+        } = self;
+
+        // The scope binding `this` is always right inside the one binding `super`.
+        let super_scope = environment.ancestor(depth.get());
+        let this_scope = environment.ancestor(depth.get().map(|depth| depth - 1));
+
+        let Some(Some(Value::Class(superclass))) = super_scope.get_value(Symbol::SUPER) else {
+            return Err(RuntimeError::SuperOutsideSubclass(*span));
+        };
+        let Some(Some(Value::Instance(instance))) = this_scope.get_value(Symbol::THIS) else {
+            return Err(RuntimeError::SuperOutsideSubclass(*span));
+        };
+
+        match superclass.find_method(*method) {
+            Some(method) => Ok(Value::Function(method.bind(instance))),
+            None => Err(RuntimeError::UndefinedProperty(*method, *span)),
+        }
+    }
+}
+
+impl Interpret for GetExpression {
+    fn evaluate(&self, environment: &Environment) -> Result<Value, RuntimeError> {
+        let Self { object, name, span } = self;
+
+        match object.evaluate(environment)? {
+            Value::Instance(instance) => instance.get(*name, *span),
+            object => Err(RuntimeError::OnlyInstancesHaveProperties(object, *span)),
+        }
+    }
+}
+
+impl Interpret for SetExpression {
+    fn evaluate(&self, environment: &Environment) -> Result<Value, RuntimeError> {
+        let Self {
+            object,
+            name,
+            value,
+            span,
+        } = self;
+
+        let Value::Instance(instance) = object.evaluate(environment)? else {
+            return Err(RuntimeError::OnlyInstancesHaveFields(*span));
+        };
+
+        let value = value.evaluate(environment)?;
+        instance.set(*name, value.clone());
+
+        Ok(value)
+    }
+}
+
+impl Interpret for ConditionalExpression {
+    fn evaluate(&self, environment: &Environment) -> Result<Value, RuntimeError> {
+        let Self {
+            condition,
+            then_branch,
+            else_branch,
+            ..
+        } = self;
+
+        // Only the chosen branch is evaluated.
+        if is_truthy(&condition.evaluate(environment)?) {
+            then_branch.evaluate(environment)
+        } else {
+            else_branch.evaluate(environment)
+        }
+    }
+}
+
+impl Interpret for LogicalExpression {
+    fn evaluate(&self, environment: &Environment) -> Result<Value, RuntimeError> {
+        let Self {
+            left,
+            operator,
+            right,
+            ..
+        } = self;
+
+        let lhs = left.evaluate(environment)?;
+
+        // Short-circuit, returning the operand that decided the result.
+        let is_decided = match operator {
+            Token::Or => is_truthy(&lhs),
+            Token::And => is_truthy(&lhs).not(),
+            _ => unreachable!(),
+        };
+
+        if is_decided {
+            Ok(lhs)
+        } else {
+            right.evaluate(environment)
+        }
+    }
+}
+
 impl Interpret for UnaryExpression {
-    fn evaluate(&self, environment: &mut Environment) -> Result<Value, RuntimeError> {
+    fn evaluate(&self, environment: &Environment) -> Result<Value, RuntimeError> {
         let Self {
             operator,
             expression,
+            span,
         } = self;
 
         let value = expression.evaluate(environment)?;
 
         let value = match operator {
-            Token::Bang => Value::Bool(is_truthy(value).not()),
+            Token::Bang => Value::Bool(is_truthy(&value).not()),
             Token::Minus => {
                 if matches!(value, Value::Number(_)).not() {
                     return Err(RuntimeError::UnaryExpressionExpectedNumber(
-                        operator.clone(),
-                        value,
+                        *operator, value, *span,
                     ));
                 }
                 Value::Number(value.to_number())
@@ -205,7 +743,36 @@ impl Interpret for UnaryExpression {
     }
 }
 
-fn is_truthy(value: Value) -> bool {
+impl Interpret for InterpolationExpression {
+    fn evaluate(&self, environment: &Environment) -> Result<Value, RuntimeError> {
+        let mut string = String::new();
+
+        // Values are formatted just like `print` shows them.
+        for part in &self.parts {
+            string.push_str(&part.evaluate(environment)?.to_string());
+        }
+
+        Ok(Value::String(string))
+    }
+}
+
+/// Read a variable from the scope the resolver found it in, or from the global scope.
+fn look_up_variable(
+    environment: &Environment,
+    identifier: Symbol,
+    depth: &ScopeDepth,
+    span: Span,
+) -> Result<Value, RuntimeError> {
+    let error = match environment.ancestor(depth.get()).get_value(identifier) {
+        Some(Some(value)) => return Ok(value),
+        Some(None) => RuntimeError::UninitializedVariable,
+        None => RuntimeError::UndefinedVariable,
+    };
+
+    Err(error(identifier, span))
+}
+
+fn is_truthy(value: &Value) -> bool {
     matches!(value, Value::Nil | Value::Bool(false)).not()
 }
 
@@ -213,20 +780,24 @@ fn check_number_operands(
     operator: &Token,
     lhs_val: &Value,
     rhs_val: &Value,
+    span: Span,
 ) -> Result<(), RuntimeError> {
     let error = match (lhs_val, rhs_val) {
         (Value::Number(_), Value::Number(_)) => return Ok(()),
         (Value::Number(_), _) => {
-            RuntimeError::BinaryExpressionExpectedNumberAtRight(operator.clone(), rhs_val.clone())
+            let operator = *operator;
+            RuntimeError::BinaryExpressionExpectedNumberAtRight(operator, rhs_val.clone(), span)
         }
         (_, Value::Number(_)) => {
-            RuntimeError::BinaryExpressionExpectedNumberAtLeft(operator.clone(), lhs_val.clone())
+            let operator = *operator;
+            RuntimeError::BinaryExpressionExpectedNumberAtLeft(operator, lhs_val.clone(), span)
         }
         _ => {
             RuntimeError::BinaryExpressionExpectedNumberBothSides(
-                operator.clone(),
-                lhs_val.clone(),
-                rhs_val.clone(),
+                *operator,
+                Box::new(lhs_val.clone()),
+                Box::new(rhs_val.clone()),
+                span,
             )
         }
     };
@@ -237,15 +808,427 @@ fn check_number_operands(
 #[derive(thiserror::Error, Debug)]
 pub enum RuntimeError {
     #[error("Expected number after unary operator '{0}'")]
-    UnaryExpressionExpectedNumber(Token, Value),
+    UnaryExpressionExpectedNumber(Token, Value, Span),
     #[error("Expected number after binary operator '{0}', found {1:?} instead.")]
-    BinaryExpressionExpectedNumberAtRight(Token, Value),
+    BinaryExpressionExpectedNumberAtRight(Token, Value, Span),
     #[error("Expected number before binary operator '{0}', found {1:?} instead.")]
-    BinaryExpressionExpectedNumberAtLeft(Token, Value),
+    BinaryExpressionExpectedNumberAtLeft(Token, Value, Span),
     #[error("Operator '{0}' should be surrounded by numbers, found {1:?} and {2:?} instead.")]
-    BinaryExpressionExpectedNumberBothSides(Token, Value, Value),
+    /// The values are boxed to keep `RuntimeError` small.
+    BinaryExpressionExpectedNumberBothSides(Token, Box<Value>, Box<Value>, Span),
     #[error("variable '{0}' is not defined")]
-    UndefinedVariable(String),
+    UndefinedVariable(Symbol, Span),
     #[error("variable '{0}' is defined but uninitialized")]
-    UninitializedVariable(String),
+    UninitializedVariable(Symbol, Span),
+    #[error("Can only call functions and classes, found {0:?} instead.")]
+    NotCallable(Value, Span),
+    #[error("Expected {0} arguments but got {1}.")]
+    ArityMismatch(usize, usize, Span),
+    #[error("Can't return from top-level code.")]
+    ReturnOutsideFunction(Span),
+    #[error("Can't use 'this' outside of a class.")]
+    ThisOutsideClass(Span),
+    #[error("Can't use 'super' outside of a subclass.")]
+    SuperOutsideSubclass(Span),
+    #[error("Superclass must be a class, found {0:?} instead.")]
+    SuperclassMustBeAClass(Value, Span),
+    #[error("Class '{0}' can't inherit from itself.")]
+    ClassInheritsFromItself(Symbol, Span),
+    #[error("Only instances have properties, found {0:?} instead.")]
+    OnlyInstancesHaveProperties(Value, Span),
+    #[error("Only instances have fields.")]
+    OnlyInstancesHaveFields(Span),
+    #[error("Undefined property '{0}'.")]
+    UndefinedProperty(Symbol, Span),
+}
+
+impl RuntimeError {
+    pub fn span(&self) -> Span {
+        match *self {
+            Self::UnaryExpressionExpectedNumber(_, _, span)
+            | Self::BinaryExpressionExpectedNumberAtRight(_, _, span)
+            | Self::BinaryExpressionExpectedNumberAtLeft(_, _, span)
+            | Self::BinaryExpressionExpectedNumberBothSides(_, _, _, span)
+            | Self::UndefinedVariable(_, span)
+            | Self::UninitializedVariable(_, span)
+            | Self::NotCallable(_, span)
+            | Self::ArityMismatch(_, _, span)
+            | Self::ReturnOutsideFunction(span)
+            | Self::ThisOutsideClass(span)
+            | Self::SuperOutsideSubclass(span)
+            | Self::SuperclassMustBeAClass(_, span)
+            | Self::ClassInheritsFromItself(_, span)
+            | Self::OnlyInstancesHaveProperties(_, span)
+            | Self::OnlyInstancesHaveFields(span)
+            | Self::UndefinedProperty(_, span) => span,
+        }
+    }
+
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        let diagnostic = Diagnostic::error("Runtime", self.to_string(), self.span());
+
+        match self {
+            Self::UndefinedVariable(identifier, _) => {
+                diagnostic.with_help(format!("declare it first, with 'var {identifier};'"))
+            }
+            Self::UninitializedVariable(identifier, _) => {
+                diagnostic.with_help(format!(
+                    "assign a value to '{identifier}' before reading it"
+                ))
+            }
+            _ => diagnostic,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{lexer::Scanner, parser::Parser, resolver::resolve_program, token::Position};
+
+    fn try_run(source_code: &str) -> Result<Environment, RuntimeError> {
+        let tokens = Scanner::new(source_code).try_scan_all().unwrap();
+        let statements = Parser::new(&tokens).try_parse().unwrap();
+        resolve_program(&statements).unwrap();
+
+        let environment = Environment::new();
+        interpret_program(statements, &environment)?;
+        Ok(environment)
+    }
+
+    fn run(source_code: &str) -> Environment {
+        try_run(source_code).unwrap()
+    }
+
+    #[test]
+    fn test_block_shadowing_is_dropped_at_block_end() {
+        let environment = run("var a = 1; { var a = 2; var b = a; }");
+
+        assert_eq!(
+            environment.get_value("a".into()),
+            Some(Some(Value::Number(1.0)))
+        );
+        assert_eq!(environment.get_value("b".into()), None);
+    }
+
+    #[test]
+    fn test_block_reads_enclosing_scope() {
+        let environment = run("var a = 1; { { var b = a; } }");
+
+        assert_eq!(
+            environment.get_value("a".into()),
+            Some(Some(Value::Number(1.0)))
+        );
+        assert!(environment.0.borrow().enclosing.is_none());
+    }
+
+    #[test]
+    fn test_assignment_updates_enclosing_scope() {
+        let environment = run("var a = 1; var b; { b = a = 2; }");
+
+        assert_eq!(
+            environment.get_value("a".into()),
+            Some(Some(Value::Number(2.0)))
+        );
+        assert_eq!(
+            environment.get_value("b".into()),
+            Some(Some(Value::Number(2.0)))
+        );
+    }
+
+    #[test]
+    fn test_if_else_picks_branch_by_truthiness() {
+        let environment = run("\
+            var a; var b;
+            if (nil) a = 1; else a = 2;
+            if (0) b = 1; else b = 2;
+        ");
+
+        assert_eq!(
+            environment.get_value("a".into()),
+            Some(Some(Value::Number(2.0)))
+        );
+        assert_eq!(
+            environment.get_value("b".into()),
+            Some(Some(Value::Number(1.0)))
+        );
+    }
+
+    #[test]
+    fn test_loops_dont_grow_the_stack() {
+        let environment = run("\
+            var i = 0; var j = 0;
+            while (i < 100000) i = i + 1;
+            for (var k = 0; k < 100000; k = k + 1) { j = j + 1; }
+        ");
+
+        assert_eq!(
+            environment.get_value("i".into()),
+            Some(Some(Value::Number(1e5)))
+        );
+        assert_eq!(
+            environment.get_value("j".into()),
+            Some(Some(Value::Number(1e5)))
+        );
+        assert_eq!(environment.get_value("k".into()), None);
+    }
+
+    #[test]
+    fn test_logical_operators_short_circuit() {
+        let environment = run("\
+            var a = 1;
+            var b = nil or \"yes\";
+            var c = false and (a = 2);
+            var d = a or (a = 3);
+        ");
+
+        assert_eq!(
+            environment.get_value("a".into()),
+            Some(Some(Value::Number(1.0)))
+        );
+        assert_eq!(
+            environment.get_value("b".into()),
+            Some(Some(Value::String("yes".into())))
+        );
+        assert_eq!(
+            environment.get_value("c".into()),
+            Some(Some(Value::Bool(false)))
+        );
+        assert_eq!(
+            environment.get_value("d".into()),
+            Some(Some(Value::Number(1.0)))
+        );
+    }
+
+    #[test]
+    fn test_conditional_only_evaluates_the_chosen_branch() {
+        let environment = run("\
+            var a = 1;
+            var b = a > 0 ? \"positive\" : (a = 2);
+            var c = nil ? (a = 3) : a == 1 ? \"one\" : \"other\";
+        ");
+
+        assert_eq!(
+            environment.get_value("a".into()),
+            Some(Some(Value::Number(1.0)))
+        );
+        assert_eq!(
+            environment.get_value("b".into()),
+            Some(Some(Value::String("positive".into())))
+        );
+        assert_eq!(
+            environment.get_value("c".into()),
+            Some(Some(Value::String("one".into())))
+        );
+    }
+
+    #[test]
+    fn test_function_calls_and_recursion() {
+        let environment = run("\
+            fun fib(n) {
+                if (n < 2) return n;
+                return fib(n - 1) + fib(n - 2);
+            }
+            fun nothing() {}
+            var a = fib(10);
+            var b = nothing();
+        ");
+
+        assert_eq!(
+            environment.get_value("a".into()),
+            Some(Some(Value::Number(55.0)))
+        );
+        assert_eq!(environment.get_value("b".into()), Some(Some(Value::Nil)));
+    }
+
+    #[test]
+    fn test_return_unwinds_nested_blocks() {
+        let environment = run("\
+            fun find() {
+                for (var i = 0; true; i = i + 1) {
+                    { if (i == 3) return i; }
+                }
+            }
+            var a = find();
+        ");
+
+        assert_eq!(
+            environment.get_value("a".into()),
+            Some(Some(Value::Number(3.0)))
+        );
+        assert!(environment.0.borrow().enclosing.is_none());
+    }
+
+    #[test]
+    fn test_closures_capture_their_environment() {
+        let environment = run("\
+            fun makeCounter() {
+                var i = 0;
+                fun count() {
+                    i = i + 1;
+                    return i;
+                }
+                return count;
+            }
+            var first = makeCounter();
+            var second = makeCounter();
+            first();
+            var a = first();
+            var b = second();
+        ");
+
+        assert_eq!(
+            environment.get_value("a".into()),
+            Some(Some(Value::Number(2.0)))
+        );
+        assert_eq!(
+            environment.get_value("b".into()),
+            Some(Some(Value::Number(1.0)))
+        );
+        assert_eq!(environment.get_value("i".into()), None);
+    }
+
+    #[test]
+    fn test_closures_are_not_affected_by_later_shadowing() {
+        let environment = run("\
+            var a = \"global\";
+            var first; var second;
+            {
+                fun show() { return a; }
+                first = show();
+                var a = \"block\";
+                second = show();
+            }
+        ");
+
+        let global = Some(Some(Value::String("global".into())));
+        assert_eq!(environment.get_value("first".into()), global);
+        assert_eq!(environment.get_value("second".into()), global);
+    }
+
+    #[test]
+    fn test_function_arity_error() {
+        let result = try_run("fun f(a, b) {} f(1);");
+
+        assert!(matches!(result, Err(RuntimeError::ArityMismatch(2, 1, _))));
+    }
+
+    #[test]
+    fn test_classes_with_fields_methods_and_initializer() {
+        let environment = run("\
+            class Point {
+                init(x, y) {
+                    this.x = x;
+                    this.y = y;
+                    return;
+                }
+                sum() {
+                    return this.x + this.y;
+                }
+            }
+            var point = Point(1, 2);
+            var sum = point.sum;
+            point.x = 10;
+            var a = sum();
+            var b = point.init(3, 4) == point;
+            var c = point.x;
+        ");
+
+        assert_eq!(
+            environment.get_value("a".into()),
+            Some(Some(Value::Number(12.0)))
+        );
+        assert_eq!(
+            environment.get_value("b".into()),
+            Some(Some(Value::Bool(true)))
+        );
+        assert_eq!(
+            environment.get_value("c".into()),
+            Some(Some(Value::Number(3.0)))
+        );
+    }
+
+    #[test]
+    fn test_inheritance_and_super_calls() {
+        let environment = run("\
+            class A {
+                init(x) { this.x = x; }
+                name() { return \"A\"; }
+                describe() { return this.name(); }
+            }
+            class B < A {
+                init(x) { super.init(x + 1); }
+                name() { return \"B\" + super.name(); }
+            }
+            class C < B {}
+            var c = C(1);
+            var a = c.describe();
+            var b = c.x;
+        ");
+
+        assert_eq!(
+            environment.get_value("a".into()),
+            Some(Some(Value::String("BA".into())))
+        );
+        assert_eq!(
+            environment.get_value("b".into()),
+            Some(Some(Value::Number(2.0)))
+        );
+    }
+
+    #[test]
+    fn test_invalid_superclass_errors() {
+        let error = try_run("var A = 1; class B < A {}").unwrap_err();
+        assert!(matches!(error, RuntimeError::SuperclassMustBeAClass(..)));
+
+        let error = try_run("class A {} class A < A {}").unwrap_err();
+        assert!(matches!(error, RuntimeError::ClassInheritsFromItself(..)));
+    }
+
+    #[test]
+    fn test_undefined_property_error() {
+        let result = try_run("class A {} A().missing;");
+
+        assert!(matches!(result, Err(RuntimeError::UndefinedProperty(..))));
+    }
+
+    #[test]
+    fn test_assignment_to_undeclared_variable_error() {
+        let result = try_run("a = 1;");
+
+        assert!(matches!(result, Err(RuntimeError::UndefinedVariable(..))));
+    }
+
+    #[test]
+    fn test_string_interpolation() {
+        let environment = run("\
+            class Cart {}
+            var name = \"Ana\";
+            var count = 2;
+            var a = \"Hello ${name}, you have ${count + 1} items in ${Cart}, ${nil} ${true}\";
+            var b = \"${\"nested ${count}\" + \"!\"} \\${not interpolated}\";
+        ");
+
+        assert_eq!(
+            environment.get_value("a".into()),
+            Some(Some(Value::String(
+                "Hello Ana, you have 3 items in Cart, nil true".into()
+            )))
+        );
+        assert_eq!(
+            environment.get_value("b".into()),
+            Some(Some(Value::String("nested 2! ${not interpolated}".into())))
+        );
+    }
+
+    #[test]
+    fn test_runtime_errors_point_to_the_failing_expression() {
+        let error = try_run("var a = 1;\nprint a +\n  -\"b\";").unwrap_err();
+
+        let RuntimeError::UnaryExpressionExpectedNumber(_, _, span) = error else {
+            panic!("unexpected error: {error}")
+        };
+        assert_eq!(span.start, Position::new(3, 3, 23));
+        assert_eq!(span.end, Position::new(3, 7, 27));
+    }
 }
@@ -1,8 +1,16 @@
 use std::{ops::Not, str::Chars};
 
 use phf::phf_map;
-
-use crate::token::{Position, Token, TokenWithPosition};
+use unicode_ident::{is_xid_continue, is_xid_start};
+use unicode_normalization::{is_nfc, UnicodeNormalization};
+use unicode_security::MixedScript;
+
+use crate::{
+    diagnostic::Diagnostic,
+    error::LexerErrorReporter,
+    symbol::Symbol,
+    token::{Position, Span, Token, TokenWithPosition},
+};
 
 /// Compiler-time generated map of keywords.
 static KEYWORDS: phf::Map<&'static str, Token> = phf_map! {
@@ -32,8 +40,20 @@ pub struct Scanner<'a> {
     chars: Chars<'a>,
     /// Current token start position.
     position: Position,
-    /// Has made any error (TODO: maybe this should be removed).
-    has_failed: bool,
+    /// String interpolations that are still open, innermost last.
+    interpolations: Vec<OpenInterpolation>,
+    /// Suspicious code that isn't an error, like identifiers that mix scripts.
+    warnings: Vec<LexerWarning>,
+    /// Yield whitespace and comments instead of skipping them.
+    keep_trivia: bool,
+}
+
+/// A `${` whose closing `}` wasn't found yet.
+struct OpenInterpolation {
+    /// Where the `${` is.
+    span: Span,
+    /// How many `{` inside of the interpolated expression are still open.
+    braces: usize,
 }
 
 impl<'a> Scanner<'a> {
@@ -42,14 +62,42 @@ impl<'a> Scanner<'a> {
             token_start: source_code,
             chars: source_code.chars(),
             position: Position::default(),
-            has_failed: false,
+            interpolations: vec![],
+            warnings: vec![],
+            keep_trivia: false,
         }
     }
 
-    pub fn try_scan_all(self) -> Result<Vec<Token>, LexerError> {
-        self.into_iter()
-            .map(|x| Ok(x?.token_type))
-            .collect::<Result<Vec<_>, _>>()
+    /// Scanner that also yields whitespace and comments as `Token::Whitespace` and
+    /// `Token::Comment`, so the source can be rebuilt from the tokens.
+    pub fn new_lossless(source_code: &'a str) -> Self {
+        Self {
+            keep_trivia: true,
+            ..Self::new(source_code)
+        }
+    }
+
+    /// Scans all tokens, errors don't stop the scanning, so all of them are reported at once.
+    pub fn try_scan_all(&mut self) -> Result<Vec<TokenWithPosition>, LexerErrorReporter> {
+        let mut tokens = vec![];
+        let mut error_reporter = LexerErrorReporter::new();
+
+        for result in self.by_ref() {
+            match result {
+                Ok(token) => tokens.push(token),
+                Err(error) => error_reporter.add_lexer_error(error),
+            }
+        }
+
+        match error_reporter.has_errors() {
+            true => Err(error_reporter),
+            false => Ok(tokens),
+        }
+    }
+
+    /// Warnings found so far, they don't stop the scanning.
+    pub fn warnings(&self) -> &[LexerWarning] {
+        &self.warnings
     }
 
     fn is_at_end(&self) -> bool {
@@ -70,16 +118,16 @@ impl<'a> Scanner<'a> {
         self.position
     }
 
-    fn advance_checked(&mut self) -> Option<char> {
-        let ch = self.chars.next();
+    /// Span from `start` up to what was consumed so far.
+    fn span_from(&self, start: Position) -> Span {
+        Span::new(start, self.position)
+    }
 
-        if ch == Some('\n') {
-            self.position.advance_line();
-        } else {
-            self.position.advance_column();
-        }
+    fn advance_checked(&mut self) -> Option<char> {
+        let ch = self.chars.next()?;
+        self.position.advance(ch);
 
-        ch
+        Some(ch)
     }
 
     fn advance(&mut self) -> char {
@@ -118,58 +166,247 @@ impl<'a> Scanner<'a> {
         matches
     }
 
-    /// Consume an identifier and check if it is a keyword.
-    fn consume_identifier(&mut self) -> Token {
-        self.advance_while(char::is_alphanumeric);
+    /// Consume an identifier and check if it is a keyword, its first char was already consumed.
+    ///
+    /// Identifiers follow Unicode's UAX #31 (plus `_`), and are NFC-normalized, so names that
+    /// look the same but are encoded differently, like `é` and `e\u{301}`, are equal.
+    fn consume_identifier(&mut self, start: Position) -> Token {
+        self.advance_while(is_xid_continue);
 
         let lexeme = self.token_lexeme();
+        // Most identifiers are already normalized, so they don't need to be copied.
+        let normalized: String;
+        let lexeme = if is_nfc(lexeme) {
+            lexeme
+        } else {
+            normalized = lexeme.nfc().collect();
+            &normalized
+        };
+
+        if let Some(keyword_token) = KEYWORDS.get(lexeme) {
+            return *keyword_token;
+        }
+
+        let is_single_script = lexeme.is_single_script();
+        let identifier = Symbol::intern(lexeme);
 
-        match KEYWORDS.get(lexeme) {
-            Some(keyword_token) => keyword_token.clone(),
-            None => Token::Identifier(lexeme.to_owned()),
+        // Mixing scripts allows names that can't be told apart, like a latin and a cyrillic `а`.
+        if is_single_script.not() {
+            let warning = LexerWarning::MixedScriptIdentifier(identifier, self.span_from(start));
+            self.warnings.push(warning);
         }
+
+        Token::Identifier(identifier)
     }
 
-    fn consume_string(&mut self) -> Result<Token, LexerError> {
-        self.advance_while(|ch| ch != '"');
+    /// Consume a block comment, its opening `/*` was already consumed, `start` is where it is.
+    ///
+    /// Block comments nest, so `/* a /* b */ c */` is a single comment.
+    fn consume_block_comment(&mut self, start: Position) -> Result<(), LexerError> {
+        let mut depth = 1;
+
+        while depth > 0 {
+            if self.matches("*/") {
+                depth -= 1;
+            } else if self.matches("/*") {
+                depth += 1;
+            } else if self.advance_checked().is_none() {
+                return Err(LexerError::UnterminatedBlockComment(self.span_from(start)));
+            }
+        }
+
+        Ok(())
+    }
 
-        if self.is_at_end() {
-            return Err(LexerError::UnterminatedString);
+    /// Consume a string, or a segment of it, up to the closing `"` or the next `${`.
+    ///
+    /// Interpolated strings are split in segments, `"a ${b} c ${d} e"` is scanned as
+    /// `Interpolation("a ")`, `Identifier("b")`, `InterpolationMiddle(" c ")`, `Identifier("d")`
+    /// and `InterpolationEnd(" e")`. Segments that come after a `}` have their own tokens, so
+    /// they can't be mistaken for a string inside of the braces.
+    fn consume_string(
+        &mut self,
+        start: Position,
+        after_interpolation: bool,
+    ) -> Result<Token, LexerError> {
+        let mut string = String::new();
+        // After an invalid escape, keep going until the closing `"`, so scanning can resume
+        // after the string.
+        let mut escape_error = None;
+
+        let token = loop {
+            let char_position = self.position;
+
+            match self.advance_checked() {
+                None => return Err(LexerError::UnterminatedString(self.span_from(start))),
+                Some('"') if after_interpolation => {
+                    break Token::InterpolationEnd(Symbol::intern(&string))
+                }
+                Some('"') => break Token::String(Symbol::intern(&string)),
+                Some('$') if self.matches("{") => {
+                    self.interpolations.push(OpenInterpolation {
+                        span: self.span_from(char_position),
+                        braces: 0,
+                    });
+                    let segment = Symbol::intern(&string);
+                    break match after_interpolation {
+                        true => Token::InterpolationMiddle(segment),
+                        false => Token::Interpolation(segment),
+                    };
+                }
+                Some('\\') => {
+                    match self.consume_escape(char_position) {
+                        Ok(ch) => string.push(ch),
+                        Err(err) => {
+                            escape_error.get_or_insert(err);
+                        }
+                    }
+                }
+                Some(ch) => string.push(ch),
+            }
+        };
+
+        match escape_error {
+            Some(err) => Err(err),
+            None => Ok(token),
         }
+    }
+
+    /// Consume what comes after a `\` in a string, `position` is where the `\` is.
+    fn consume_escape(&mut self, position: Position) -> Result<char, LexerError> {
+        let ch = match self.peek_checked() {
+            Some('n') => '\n',
+            Some('t') => '\t',
+            Some('r') => '\r',
+            Some('\\') => '\\',
+            Some('"') => '"',
+            Some('0') => '\0',
+            Some('$') => '$',
+            Some('u') => {
+                self.advance();
+                return self.consume_unicode_escape(position);
+            }
+            Some(ch) => {
+                self.advance();
+                return Err(LexerError::UnknownEscape(ch, self.span_from(position)));
+            }
+            // The caller reports the unterminated string.
+            None => return Err(LexerError::UnknownEscape('\0', self.span_from(position))),
+        };
 
-        // Skip the closing "
         self.advance();
+        Ok(ch)
+    }
 
-        // Trim the surrounding quotes.
-        let string = self.token_lexeme().trim_matches('"');
+    /// Consume the `{1F600}` part of `\u{1F600}`.
+    fn consume_unicode_escape(&mut self, position: Position) -> Result<char, LexerError> {
+        if self.matches("{").not() {
+            return Err(LexerError::MalformedUnicodeEscape(self.span_from(position)));
+        }
+
+        let digits_start = self.chars.as_str();
+        self.advance_while(|ch| ch.is_ascii_hexdigit());
+        let digits = &digits_start[..digits_start.len() - self.chars.as_str().len()];
+
+        if digits.is_empty() || digits.len() > 6 || self.matches("}").not() {
+            return Err(LexerError::MalformedUnicodeEscape(self.span_from(position)));
+        }
+
+        // Can't overflow, there are at most 6 hex digits.
+        let code_point = u32::from_str_radix(digits, 16).unwrap();
 
-        Ok(Token::String(string.into()))
+        char::from_u32(code_point).ok_or(LexerError::InvalidCodePoint(
+            code_point,
+            self.span_from(position),
+        ))
     }
 
-    fn consume_number(&mut self) -> Result<Token, LexerError> {
-        self.advance_while(|ch| ch.is_ascii_digit());
+    /// Consume a number, its first digit was already consumed.
+    ///
+    /// Digits can be separated by `_`, as in `1_000_000`.
+    fn consume_number(&mut self, start: Position) -> Result<Token, LexerError> {
+        if self.token_lexeme() == "0" {
+            let radix = match self.peek() {
+                'x' => Some((16, "0x")),
+                'o' => Some((8, "0o")),
+                'b' => Some((2, "0b")),
+                _ => None,
+            };
+
+            if let Some((radix, prefix)) = radix {
+                self.advance();
+                return self.consume_radix_number(radix, prefix, start);
+            }
+        }
+
+        self.consume_decimal_digits(start)?;
 
         // Look for a fractional part.
         if self.peek_checked() == Some('.') && self.peek_next().is_ascii_digit() {
             // Consume the "."
             self.advance();
 
-            self.advance_while(|ch| ch.is_ascii_digit());
+            self.consume_decimal_digits(start)?;
+        }
+
+        // Look for an exponent, like in `6.02e23`.
+        if matches!(self.peek(), 'e' | 'E') {
+            self.advance();
+            if matches!(self.peek(), '+' | '-') {
+                self.advance();
+            }
+
+            if self.peek().is_ascii_digit().not() {
+                return Err(LexerError::MissingExponentDigits(self.span_from(start)));
+            }
+            self.consume_decimal_digits(start)?;
         }
 
         // Parse number
-        match self.token_lexeme().parse() {
+        match self.token_lexeme().replace('_', "").parse() {
             Ok(number) => Ok(Token::Number(number)),
-            Err(_err) => Err(LexerError::InvalidNumber),
+            Err(_err) => Err(LexerError::InvalidNumber(self.span_from(start))),
+        }
+    }
+
+    /// Consume digits and their `_` separators, which can't come last.
+    fn consume_decimal_digits(&mut self, start: Position) -> Result<(), LexerError> {
+        self.advance_while(|ch| ch.is_ascii_digit() || ch == '_');
+
+        match self.token_lexeme().ends_with('_') {
+            true => Err(LexerError::TrailingDigitSeparator(self.span_from(start))),
+            false => Ok(()),
         }
     }
 
-    fn error(&mut self, message: impl ToString) {
-        fn report(position: Position, where_: String, message: String) {
-            eprintln!("[line {position}] Error{where_}: {message}");
+    /// Consume the digits of a `0x`, `0o` or `0b` number, its prefix was already consumed.
+    fn consume_radix_number(
+        &mut self,
+        radix: u32,
+        prefix: &'static str,
+        start: Position,
+    ) -> Result<Token, LexerError> {
+        // Take all alphanumeric chars, so a digit that's invalid for this radix is reported,
+        // instead of starting the next token.
+        self.advance_while(|ch| ch.is_ascii_alphanumeric() || ch == '_');
+        let digits = &self.token_lexeme()[prefix.len()..];
+
+        if digits.chars().all(|ch| ch == '_') {
+            return Err(LexerError::MissingDigits(prefix, self.span_from(start)));
         }
-        self.has_failed = true;
-        report(self.position, "".to_string(), message.to_string());
+        if digits.ends_with('_') {
+            return Err(LexerError::TrailingDigitSeparator(self.span_from(start)));
+        }
+
+        let mut number = 0.0;
+        for ch in digits.chars().filter(|&ch| ch != '_') {
+            let Some(digit) = ch.to_digit(radix) else {
+                return Err(LexerError::InvalidDigit(ch, radix, self.span_from(start)));
+            };
+            number = number * f64::from(radix) + f64::from(digit);
+        }
+
+        Ok(Token::Number(number))
     }
 }
 
@@ -184,7 +421,11 @@ impl Iterator for Scanner<'_> {
 
         let token_type = loop {
             if self.is_at_end() {
-                return None;
+                // Report only the outermost one, the inner ones are inside of it anyway.
+                let open = self.interpolations.first()?;
+                let error = LexerError::UnterminatedInterpolation(open.span);
+                self.interpolations.clear();
+                return Some(Err(error));
             }
 
             match () {
@@ -192,54 +433,179 @@ impl Iterator for Scanner<'_> {
                 _ if self.matches("==") => break EqualEqual,
                 _ if self.matches("<=") => break LessEqual,
                 _ if self.matches(">=") => break GreaterEqual,
-                _ if self.matches("//") => self.advance_while(|ch| ch != '\n'),
+                _ if self.matches("//") => {
+                    self.advance_while(|ch| ch != '\n');
+                    if self.keep_trivia {
+                        break Comment;
+                    }
+                    // Start over, the comment may have run into the end of the input.
+                    token_position = self.reset_token();
+                    continue;
+                }
+                _ if self.matches("/*") => {
+                    if let Err(err) = self.consume_block_comment(token_position) {
+                        return Some(Err(err));
+                    }
+                    if self.keep_trivia {
+                        break Comment;
+                    }
+                    token_position = self.reset_token();
+                    continue;
+                }
                 _ => {}
             }
 
             match self.advance_checked()? {
                 '(' => break LeftParen,
                 ')' => break RightParen,
-                '{' => break LeftBrace,
-                '}' => break RightBrace,
+                '{' => {
+                    if let Some(open) = self.interpolations.last_mut() {
+                        open.braces += 1;
+                    }
+                    break LeftBrace;
+                }
+                '}' => {
+                    match self.interpolations.last_mut() {
+                        Some(open) if open.braces == 0 => {
+                            // This closes the interpolation, the string continues after it.
+                            self.interpolations.pop();
+                            match self.consume_string(token_position, true) {
+                                Ok(string) => break string,
+                                Err(err) => return Some(Err(err)),
+                            }
+                        }
+                        Some(open) => open.braces -= 1,
+                        None => {}
+                    }
+                    break RightBrace;
+                }
                 ',' => break Comma,
                 '.' => break Dot,
                 '-' => break Minus,
                 '+' => break Plus,
                 ';' => break Semicolon,
                 '*' => break Star,
+                '?' => break Question,
+                ':' => break Colon,
                 '!' => break Bang,
                 '=' => break Equal,
                 '<' => break Less,
                 '>' => break Greater,
                 '/' => break Slash,
                 '"' => {
-                    match self.consume_string() {
+                    match self.consume_string(token_position, false) {
                         Ok(string) => break string,
                         Err(err) => return Some(Err(err)),
                     }
                 }
                 '0'..='9' => {
-                    match self.consume_number() {
+                    match self.consume_number(token_position) {
                         Ok(number) => break number,
                         Err(err) => return Some(Err(err)),
                     }
                 }
-                c if c.is_alphabetic() => break self.consume_identifier(),
-                c if c.is_whitespace() => token_position = self.reset_token(),
-                _ => self.error("Unexpected character."),
+                c if c == '_' || is_xid_start(c) => break self.consume_identifier(token_position),
+                c if c.is_whitespace() => {
+                    if self.keep_trivia {
+                        self.advance_while(char::is_whitespace);
+                        break Whitespace;
+                    }
+                    token_position = self.reset_token();
+                }
+                ch => {
+                    return Some(Err(LexerError::UnexpectedCharacter(
+                        ch,
+                        self.span_from(token_position),
+                    )))
+                }
             }
         };
 
-        Some(Ok(TokenWithPosition::new(token_type, token_position)))
+        let span = Span::new(token_position, self.position);
+        Some(Ok(TokenWithPosition::new(token_type, span)))
     }
 }
 
 #[derive(thiserror::Error, Debug)]
 pub enum LexerError {
     #[error("Unterminated string.")]
-    UnterminatedString,
+    UnterminatedString(Span),
+    #[error("Unterminated block comment, expected '*/'.")]
+    UnterminatedBlockComment(Span),
+    #[error("Unterminated string interpolation, expected '}}'.")]
+    UnterminatedInterpolation(Span),
     #[error("Invalid number.")]
-    InvalidNumber,
+    InvalidNumber(Span),
+    #[error("Expected digits after '{0}'.")]
+    MissingDigits(&'static str, Span),
+    #[error("Invalid digit {0:?} in a base {1} number.")]
+    InvalidDigit(char, u32, Span),
+    #[error("Expected digits in the exponent.")]
+    MissingExponentDigits(Span),
+    #[error("Numbers can't end with a '_' separator.")]
+    TrailingDigitSeparator(Span),
+    #[error("Unexpected character {0:?}.")]
+    UnexpectedCharacter(char, Span),
+    #[error("Unknown escape sequence {0:?}.")]
+    UnknownEscape(char, Span),
+    #[error("Malformed unicode escape, expected 1 to 6 hex digits, like '\\u{{1F600}}'.")]
+    MalformedUnicodeEscape(Span),
+    #[error("Invalid unicode code point '{0:X}'.")]
+    InvalidCodePoint(u32, Span),
+}
+
+impl LexerError {
+    pub fn span(&self) -> Span {
+        match *self {
+            Self::UnterminatedString(span)
+            | Self::UnterminatedBlockComment(span)
+            | Self::UnterminatedInterpolation(span)
+            | Self::InvalidNumber(span)
+            | Self::MissingDigits(_, span)
+            | Self::InvalidDigit(_, _, span)
+            | Self::MissingExponentDigits(span)
+            | Self::TrailingDigitSeparator(span)
+            | Self::UnexpectedCharacter(_, span)
+            | Self::UnknownEscape(_, span)
+            | Self::MalformedUnicodeEscape(span)
+            | Self::InvalidCodePoint(_, span) => span,
+        }
+    }
+
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        let diagnostic = Diagnostic::error("Lexer", self.to_string(), self.span());
+
+        match self {
+            Self::UnterminatedBlockComment(_) => {
+                diagnostic.with_note("block comments nest, each '/*' needs its own '*/'")
+            }
+            Self::UnterminatedInterpolation(_) => diagnostic.with_label("opened here"),
+            Self::UnknownEscape(..) => {
+                diagnostic
+                    .with_help("valid escapes are \\n, \\t, \\r, \\\\, \\\", \\0, \\$ and \\u{...}")
+            }
+            Self::TrailingDigitSeparator(_) => diagnostic.with_help("remove the trailing '_'"),
+            _ => diagnostic,
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum LexerWarning {
+    #[error("Identifier '{0}' mixes characters of different scripts, some may be confusable.")]
+    MixedScriptIdentifier(Symbol, Span),
+}
+
+impl LexerWarning {
+    pub fn span(&self) -> Span {
+        match *self {
+            Self::MixedScriptIdentifier(_, span) => span,
+        }
+    }
+
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        Diagnostic::warning("Lexer", self.to_string(), self.span())
+    }
 }
 
 #[cfg(test)]
@@ -248,6 +614,19 @@ mod tests {
 
     use super::*;
 
+    /// Span of a token in a single-line ASCII source, where byte offsets trail columns by one.
+    fn span(start_column: usize, end_column: usize) -> Span {
+        Span::new(
+            Position::new(1, start_column, start_column - 1),
+            Position::new(1, end_column, end_column - 1),
+        )
+    }
+
+    /// Error message prefixed by where it starts.
+    fn located(error: &LexerError) -> std::string::String {
+        format!("[{}] {error}", error.span().start)
+    }
+
     #[rustfmt::skip]
     #[test]
     fn test_scanning_statement() {
@@ -256,24 +635,24 @@ mod tests {
         let mut scanner = Scanner::new(source_code);
         let mut n = || scanner.next().unwrap().unwrap();
 
-        assert_eq!(n(), TokenWithPosition::new(Var                     , Position::new(1, 1)));
-        assert_eq!(n(), TokenWithPosition::new(Identifier("foo".into()), Position::new(1, 5)));
-        assert_eq!(n(), TokenWithPosition::new(Equal                   , Position::new(1, 9)));
-        assert_eq!(n(), TokenWithPosition::new(Bang                    , Position::new(1, 11)));
-        assert_eq!(n(), TokenWithPosition::new(String("text".into())   , Position::new(1, 12)));
-        assert_eq!(n(), TokenWithPosition::new(Plus                    , Position::new(1, 19)));
-        assert_eq!(n(), TokenWithPosition::new(LeftParen               , Position::new(1, 21)));
-        assert_eq!(n(), TokenWithPosition::new(Identifier("min".into()), Position::new(1, 22)));
-        assert_eq!(n(), TokenWithPosition::new(Plus                    , Position::new(1, 26)));
-        assert_eq!(n(), TokenWithPosition::new(Identifier("max".into()), Position::new(1, 28)));
-        assert_eq!(n(), TokenWithPosition::new(RightParen              , Position::new(1, 31)));
-        assert_eq!(n(), TokenWithPosition::new(Slash                   , Position::new(1, 33)));
-        assert_eq!(n(), TokenWithPosition::new(Number(2.0)             , Position::new(1, 35)));
-        assert_eq!(n(), TokenWithPosition::new(Or                      , Position::new(1, 37)));
-        assert_eq!(n(), TokenWithPosition::new(Bool(true)              , Position::new(1, 40)));
-        assert_eq!(n(), TokenWithPosition::new(And                     , Position::new(1, 45)));
-        assert_eq!(n(), TokenWithPosition::new(Bool(false)             , Position::new(1, 49)));
-        assert_eq!(n(), TokenWithPosition::new(Semicolon               , Position::new(1, 54)));
+        assert_eq!(n(), TokenWithPosition::new(Var                     , span(1 , 4 )));
+        assert_eq!(n(), TokenWithPosition::new(Identifier("foo".into()), span(5 , 8 )));
+        assert_eq!(n(), TokenWithPosition::new(Equal                   , span(9 , 10)));
+        assert_eq!(n(), TokenWithPosition::new(Bang                    , span(11, 12)));
+        assert_eq!(n(), TokenWithPosition::new(String("text".into())   , span(12, 18)));
+        assert_eq!(n(), TokenWithPosition::new(Plus                    , span(19, 20)));
+        assert_eq!(n(), TokenWithPosition::new(LeftParen               , span(21, 22)));
+        assert_eq!(n(), TokenWithPosition::new(Identifier("min".into()), span(22, 25)));
+        assert_eq!(n(), TokenWithPosition::new(Plus                    , span(26, 27)));
+        assert_eq!(n(), TokenWithPosition::new(Identifier("max".into()), span(28, 31)));
+        assert_eq!(n(), TokenWithPosition::new(RightParen              , span(31, 32)));
+        assert_eq!(n(), TokenWithPosition::new(Slash                   , span(33, 34)));
+        assert_eq!(n(), TokenWithPosition::new(Number(2.0)             , span(35, 36)));
+        assert_eq!(n(), TokenWithPosition::new(Or                      , span(37, 39)));
+        assert_eq!(n(), TokenWithPosition::new(Bool(true)              , span(40, 44)));
+        assert_eq!(n(), TokenWithPosition::new(And                     , span(45, 48)));
+        assert_eq!(n(), TokenWithPosition::new(Bool(false)             , span(49, 54)));
+        assert_eq!(n(), TokenWithPosition::new(Semicolon               , span(54, 55)));
         assert!(scanner.next().is_none());
     }
 
@@ -284,17 +663,17 @@ mod tests {
             let mut scanner = Scanner::new(source_code);
             let mut n = || scanner.next().unwrap().unwrap();
 
-            assert_eq!(n(), TokenWithPosition::new(Number(1.0), Position::new(1, 1)));
-            assert_eq!(n(), TokenWithPosition::new(Minus      , Position::new(1, 3)));
-            assert_eq!(n(), TokenWithPosition::new(LeftParen  , Position::new(1, 5)));
-            assert_eq!(n(), TokenWithPosition::new(Number(2.0), Position::new(1, 6)));
-            assert_eq!(n(), TokenWithPosition::new(Star       , Position::new(1, 8)));
-            assert_eq!(n(), TokenWithPosition::new(Number(3.0), Position::new(1, 10)));
-            assert_eq!(n(), TokenWithPosition::new(RightParen , Position::new(1, 11)));
-            assert_eq!(n(), TokenWithPosition::new(Less       , Position::new(1, 13)));
-            assert_eq!(n(), TokenWithPosition::new(Number(4.0), Position::new(1, 15)));
-            assert_eq!(n(), TokenWithPosition::new(EqualEqual , Position::new(1, 17)));
-            assert_eq!(n(), TokenWithPosition::new(Bool(false), Position::new(1, 20)));
+            assert_eq!(n(), TokenWithPosition::new(Number(1.0), span(1 , 2 )));
+            assert_eq!(n(), TokenWithPosition::new(Minus      , span(3 , 4 )));
+            assert_eq!(n(), TokenWithPosition::new(LeftParen  , span(5 , 6 )));
+            assert_eq!(n(), TokenWithPosition::new(Number(2.0), span(6 , 7 )));
+            assert_eq!(n(), TokenWithPosition::new(Star       , span(8 , 9 )));
+            assert_eq!(n(), TokenWithPosition::new(Number(3.0), span(10, 11)));
+            assert_eq!(n(), TokenWithPosition::new(RightParen , span(11, 12)));
+            assert_eq!(n(), TokenWithPosition::new(Less       , span(13, 14)));
+            assert_eq!(n(), TokenWithPosition::new(Number(4.0), span(15, 16)));
+            assert_eq!(n(), TokenWithPosition::new(EqualEqual , span(17, 19)));
+            assert_eq!(n(), TokenWithPosition::new(Bool(false), span(20, 25)));
             assert!(scanner.next().is_none());
         }
 
@@ -308,12 +687,259 @@ mod tests {
         assert!(Scanner::new(source_code).next().is_none());
     }
 
+    #[test]
+    fn test_nested_block_comments() {
+        let source_code = "a /* one /* two\n */ still\n comment */b\n/**/ c";
+        let mut scanner = Scanner::new(source_code);
+        let mut n = || scanner.next().unwrap().unwrap();
+
+        let position = |line, column, offset| Position::new(line, column, offset);
+        assert_eq!(n().span, Span::new(position(1, 1, 0), position(1, 2, 1)));
+        assert_eq!(
+            n(),
+            TokenWithPosition::new(
+                Identifier("b".into()),
+                Span::new(position(3, 12, 37), position(3, 13, 38))
+            )
+        );
+        assert_eq!(n().span, Span::new(position(4, 6, 44), position(4, 7, 45)));
+        assert!(scanner.next().is_none());
+    }
+
+    #[test]
+    fn test_unterminated_block_comment_error() {
+        let source_code = "a;\n  /* /* */\n";
+
+        let errors: Vec<_> = Scanner::new(source_code)
+            .filter_map(Result::err)
+            .map(|error| located(&error))
+            .collect();
+
+        assert_eq!(errors, ["[2:3] Unterminated block comment, expected '*/'."]);
+    }
+
     #[test]
     fn test_dont_panic_on_comment_at_end() {
         let source_code = "// asjdnasjdnasjd";
         assert!(Scanner::new(source_code).next().is_none());
     }
 
+    #[test]
+    fn test_scanning_continues_after_errors() {
+        let source_code = "var a = @1;\nvar # = \"b;";
+
+        let errors: Vec<_> = Scanner::new(source_code)
+            .filter_map(Result::err)
+            .map(|error| located(&error))
+            .collect();
+
+        assert_eq!(
+            errors,
+            [
+                "[1:9] Unexpected character '@'.",
+                "[2:5] Unexpected character '#'.",
+                "[2:9] Unterminated string.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_scanning_string_escapes() {
+        let source_code = r#""\"quoted\"\n\t\r\\\0 \u{1F600} \u{e7}" "ends with quote\"""#;
+        let tokens = Scanner::new(source_code).try_scan_all().unwrap();
+        let strings: Vec<_> = tokens.into_iter().map(|token| token.token_type).collect();
+
+        assert_eq!(
+            strings,
+            [
+                String("\"quoted\"\n\t\r\\\0 😀 ç".into()),
+                String("ends with quote\"".into())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid_string_escapes_errors() {
+        let source_code = r#""\q" "\u1F600" "\u{}" "\u{D800}" "\u{1234567}" "ok""#;
+
+        let results: Vec<_> = Scanner::new(source_code)
+            .map(|result| result.map_err(|error| located(&error)))
+            .collect();
+
+        let malformed = "Malformed unicode escape, expected 1 to 6 hex digits, like '\\u{1F600}'.";
+        assert_eq!(
+            results,
+            [
+                Err("[1:2] Unknown escape sequence 'q'.".into()),
+                Err(format!("[1:7] {malformed}")),
+                Err(format!("[1:17] {malformed}")),
+                Err("[1:24] Invalid unicode code point 'D800'.".into()),
+                Err(format!("[1:35] {malformed}")),
+                Ok(TokenWithPosition::new(String("ok".into()), span(48, 52))),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_scanning_string_interpolation() {
+        let source_code = r#""a ${ {} } b ${"c${d}"}" "${"#;
+
+        let tokens: Vec<_> = Scanner::new(source_code)
+            .map(|result| {
+                result
+                    .map(|token| token.token_type)
+                    .map_err(|error| located(&error))
+            })
+            .collect();
+
+        assert_eq!(
+            tokens,
+            [
+                Ok(Interpolation("a ".into())),
+                Ok(LeftBrace),
+                Ok(RightBrace),
+                Ok(InterpolationMiddle(" b ".into())),
+                Ok(Interpolation("c".into())),
+                Ok(Identifier("d".into())),
+                Ok(InterpolationEnd("".into())),
+                Ok(InterpolationEnd("".into())),
+                Ok(Interpolation("".into())),
+                Err("[1:27] Unterminated string interpolation, expected '}'.".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unterminated_interpolation_ending_in_comment_error() {
+        let source_code = r#"print "a ${ 1 // x"#;
+
+        let errors: Vec<_> = Scanner::new(source_code)
+            .filter_map(Result::err)
+            .map(|error| located(&error))
+            .collect();
+
+        assert_eq!(
+            errors,
+            ["[1:10] Unterminated string interpolation, expected '}'."]
+        );
+    }
+
+    #[test]
+    fn test_scanning_number_literals() {
+        let source_code = "0xFF 0xff_ff 0b1010 0o17 1_000_000 6.02e23 1E-3 2.5e+2 0 1.5";
+
+        let numbers: Vec<_> = Scanner::new(source_code)
+            .map(|result| result.unwrap().token_type)
+            .collect();
+
+        assert_eq!(
+            numbers,
+            [
+                Number(255.0),
+                Number(65535.0),
+                Number(10.0),
+                Number(15.0),
+                Number(1_000_000.0),
+                Number(6.02e23),
+                Number(1e-3),
+                Number(250.0),
+                Number(0.0),
+                Number(1.5),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_malformed_number_errors() {
+        let source_code = "0x; 0b102; 1e; 2e+; 1_; 1_.5; 1.5_; 0x_; 0o8";
+
+        let errors: Vec<_> = Scanner::new(source_code)
+            .filter_map(Result::err)
+            .map(|error| located(&error))
+            .collect();
+
+        assert_eq!(
+            errors,
+            [
+                "[1:1] Expected digits after '0x'.",
+                "[1:5] Invalid digit '2' in a base 2 number.",
+                "[1:12] Expected digits in the exponent.",
+                "[1:16] Expected digits in the exponent.",
+                "[1:21] Numbers can't end with a '_' separator.",
+                "[1:25] Numbers can't end with a '_' separator.",
+                "[1:31] Numbers can't end with a '_' separator.",
+                "[1:37] Expected digits after '0x'.",
+                "[1:42] Invalid digit '8' in a base 8 number.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_scanning_unicode_identifiers() {
+        // The second `café` is spelled with a combining accent.
+        let source_code = "_tmp ação 变量 número_2 café cafe\u{301}";
+
+        let identifiers: Vec<_> = Scanner::new(source_code)
+            .map(|result| result.unwrap().token_type)
+            .collect();
+
+        assert_eq!(
+            identifiers,
+            [
+                Identifier("_tmp".into()),
+                Identifier("ação".into()),
+                Identifier("变量".into()),
+                Identifier("número_2".into()),
+                Identifier("café".into()),
+                Identifier("café".into()),
+            ]
+        );
+
+        let error = Scanner::new("😀").next().unwrap().unwrap_err();
+        assert!(matches!(error, LexerError::UnexpectedCharacter('😀', _)));
+    }
+
+    #[test]
+    fn test_mixed_script_identifier_warning() {
+        // The `а` in the middle is cyrillic.
+        let mut scanner = Scanner::new("var p\u{430}ss = 1; var 変数 = 2;");
+        scanner.try_scan_all().unwrap();
+
+        let warnings: Vec<_> = scanner
+            .warnings()
+            .iter()
+            .map(|warning| format!("[{}] {warning}", warning.span().start))
+            .collect();
+
+        assert_eq!(
+            warnings,
+            ["[1:5] Identifier 'p\u{430}ss' mixes characters of different scripts, some may be confusable."]
+        );
+    }
+
+    #[test]
+    fn test_lossless_scanning_keeps_trivia() {
+        let source_code = "a /* b */\n// c\n;";
+
+        let tokens: Vec<_> = Scanner::new_lossless(source_code)
+            .map(|result| result.unwrap())
+            .map(|token| (token.token_type, &source_code[token.span.byte_range()]))
+            .collect();
+
+        assert_eq!(
+            tokens,
+            [
+                (Identifier("a".into()), "a"),
+                (Whitespace, " "),
+                (Comment, "/* b */"),
+                (Whitespace, "\n"),
+                (Comment, "// c"),
+                (Whitespace, "\n"),
+                (Semicolon, ";"),
+            ]
+        );
+    }
+
     #[test]
     fn test_unterminated_string_error() {
         let source_code = r#"
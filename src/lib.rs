@@ -0,0 +1,16 @@
+//! Lox interpreter, also usable as a library by tools that work with Lox source code, like
+//! formatters, refactoring tools and doc generators, see `syntax` for the lossless syntax tree.
+
+mod ast_printer;
+pub mod diagnostic;
+pub mod error;
+pub mod expression;
+pub mod interpreter;
+pub mod lexer;
+pub mod parser;
+pub mod resolver;
+pub mod statement;
+pub mod symbol;
+pub mod syntax;
+pub mod token;
+mod utils;
@@ -1,15 +1,3 @@
-#![feature(box_syntax)]
-
-mod ast_printer;
-mod error;
-mod expression;
-mod interpreter;
-mod lexer;
-mod parser;
-mod statement;
-mod token;
-mod utils;
-
 use std::{
     env,
     ffi::OsString,
@@ -19,18 +7,18 @@ use std::{
 };
 
 use fs_err as fs;
-
-use crate::{
-    error::{ParserErrorReporter, Result},
-    expression::Expression,
+use lox::{
+    error::{Error, Result},
     interpreter::{interpret_program, Environment},
     lexer::Scanner,
     parser::Parser,
+    resolver::resolve_program,
+    token::TokenWithPosition,
 };
 
 fn main() {
     run().unwrap_or_else(|err| {
-        eprint!("{err}");
+        eprintln!("{err}");
         process::exit(1);
     });
 }
@@ -50,7 +38,7 @@ fn run() -> Result<()> {
 }
 
 fn start_repl() -> Result<()> {
-    let mut state = Environment::new();
+    let state = Environment::new();
 
     loop {
         print!("> ");
@@ -64,7 +52,7 @@ fn start_repl() -> Result<()> {
         }
 
         // If errors appear, report them and keep the REPL running.
-        match interpret_lox_with_state(&line, &mut state) {
+        match interpret_lox_with_state("<stdin>", &line, &state) {
             Ok(_) => {}
             Err(err) => eprintln!("{err}"),
         }
@@ -73,19 +61,33 @@ fn start_repl() -> Result<()> {
 
 fn interpret_lox_file(path: &Path) -> Result<()> {
     let file_contents = fs::read_to_string(path)?;
-    interpret_lox(&file_contents)?;
+    interpret_lox(&path.display().to_string(), &file_contents)?;
 
     Ok(())
 }
 
-fn interpret_lox(text: &str) -> Result<()> {
-    let mut environment = Environment::new();
-    interpret_lox_with_state(text, &mut environment)
+fn interpret_lox(file_name: &str, text: &str) -> Result<()> {
+    let environment = Environment::new();
+    interpret_lox_with_state(file_name, text, &environment)
+}
+
+/// Errors and warnings are shown along with the code they point at, `file_name` is shown too.
+fn interpret_lox_with_state(file_name: &str, text: &str, state: &Environment) -> Result<()> {
+    let mut scanner = Scanner::new(text);
+    let tokens = scanner.try_scan_all();
+    for warning in scanner.warnings() {
+        eprintln!("{}\n", warning.to_diagnostic().render(file_name, text));
+    }
+
+    tokens
+        .map_err(Error::from)
+        .and_then(|tokens| interpret_tokens(&tokens, state))
+        .map_err(|err| err.in_source(file_name, text))
 }
 
-fn interpret_lox_with_state(text: &str, state: &mut Environment) -> Result<()> {
-    let tokens = Scanner::new(text).try_scan_all()?;
-    let statements = Parser::new(&tokens).try_parse()?;
+fn interpret_tokens(tokens: &[TokenWithPosition], state: &Environment) -> Result<()> {
+    let statements = Parser::new(tokens).try_parse()?;
+    resolve_program(&statements)?;
 
     interpret_program(statements, state).map_err(From::from)
 }
@@ -5,69 +5,166 @@
 //! ```txt
 //!   program        → declaration* EOF ;
 //!
-//!   declaration    → varDecl
+//!   declaration    → classDecl
+//!                  | funDecl
+//!                  | varDecl
 //!                  | statement ;
 //!
+//!   classDecl      → "class" IDENTIFIER ( "<" IDENTIFIER )?
+//!                    "{" function* "}" ;
+//!   funDecl        → "fun" function ;
+//!   function       → IDENTIFIER "(" parameters? ")" block ;
+//!   parameters     → IDENTIFIER ( "," IDENTIFIER )* ;
 //!   varDecl        → "var" IDENTIFIER ( "=" expression )? ";" ;
 //!
 //!   statement      → exprStmt
-//!                    | printStmt ;
+//!                    | forStmt
+//!                    | ifStmt
+//!                    | printStmt
+//!                    | returnStmt
+//!                    | whileStmt
+//!                    | block ;
 //!
 //!   exprStmt       → expression ";" ;
+//!   forStmt        → "for" "(" ( varDecl | exprStmt | ";" )
+//!                    expression? ";"
+//!                    expression? ")" statement ;
+//!   ifStmt         → "if" "(" expression ")" statement
+//!                    ( "else" statement )? ;
 //!   printStmt      → "print" expression ";" ;
+//!   returnStmt     → "return" expression? ";" ;
+//!   whileStmt      → "while" "(" expression ")" statement ;
+//!   block          → "{" declaration* "}" ;
 //!
-//!   expression     → equality ;
+//!   expression     → assignment ;
+//!   assignment     → ( call "." )? IDENTIFIER "=" assignment
+//!                    | conditional ;
+//!   conditional    → logic_or ( "?" expression ":" conditional )? ;
+//!   logic_or       → logic_and ( "or" logic_and )* ;
+//!   logic_and      → equality ( "and" equality )* ;
 //!   equality       → comparison ( ( "!=" | "==" ) comparison )* ;
 //!   comparison     → term ( ( ">" | ">=" | "<" | "<=" ) term )* ;
 //!   term           → factor ( ( "-" | "+" ) factor )* ;
 //!   factor         → unary ( ( "/" | "*" ) unary )* ;
 //!   unary          → ( "!" | "-" ) unary
-//!                    | literal ;
-//!   primary        → "true" | "false" | "nil"
-//!                    | NUMBER | STRING
+//!                    | call ;
+//!   call           → primary ( "(" arguments? ")" | "." IDENTIFIER )* ;
+//!   arguments      → expression ( "," expression )* ;
+//!   primary        → "true" | "false" | "nil" | "this"
+//!                    | NUMBER | STRING | interpolation
 //!                    | "(" expression ")"
-//!                    | IDENTIFIER ;
+//!                    | IDENTIFIER
+//!                    | "super" "." IDENTIFIER ;
+//!   interpolation  → INTERPOLATION expression
+//!                    ( INTERPOLATION_MIDDLE expression )* INTERPOLATION_END ;
 //! ```
+//!
+//! Expressions are parsed by a Pratt parser, the operators and their precedence levels, which
+//! follow the rules above, are registered in `Parser::parse_rule`.
 
-use std::slice;
+use std::{rc::Rc, slice};
 
 use crate::{
-    expression::{BinaryExpression, Expression, LiteralExpression, UnaryExpression},
-    statement::Statement,
-    token::Token::{self, *},
-    ParserErrorReporter,
+    diagnostic::Diagnostic,
+    error::ParserErrorReporter,
+    expression::{
+        AssignExpression, BinaryExpression, CallExpression, ConditionalExpression, Expression,
+        GetExpression, InterpolationExpression, LiteralExpression, LogicalExpression, ScopeDepth,
+        SetExpression, SuperExpression, UnaryExpression,
+    },
+    statement::{ClassDeclaration, FunctionDeclaration, Statement, StatementKind},
+    symbol::Symbol,
+    syntax::{SyntaxEvent, SyntaxKind},
+    token::{
+        Position, Span,
+        Token::{self, *},
+        TokenWithPosition,
+    },
 };
 
 #[derive(Debug)]
 pub struct Parser<'a> {
-    tokens_iter: slice::Iter<'a, Token>,
+    tokens_iter: slice::Iter<'a, TokenWithPosition>,
+    /// Last consumed token, used to know where the node being parsed ends.
+    previous: Option<&'a TokenWithPosition>,
+    /// Delimiters consumed and not closed yet, a `)` or `}` without an open one is stray.
+    open_parens: usize,
+    open_braces: usize,
     error_reporter: ParserErrorReporter,
+    /// Only recorded when building a CST, see `Parser::new_lossless`.
+    syntax_events: Option<Vec<SyntaxEvent>>,
 }
 
 impl<'a> Parser<'a> {
     /// Creates a new token parser.
-    pub fn new(tokens: &'a [Token]) -> Self {
+    pub fn new(tokens: &'a [TokenWithPosition]) -> Self {
         Self {
             tokens_iter: tokens.iter(),
+            previous: None,
+            open_parens: 0,
+            open_braces: 0,
             error_reporter: ParserErrorReporter::new(),
+            syntax_events: None,
+        }
+    }
+
+    /// Creates a parser that also records `SyntaxEvent`s, to build a CST out of them.
+    pub fn new_lossless(tokens: &'a [TokenWithPosition]) -> Self {
+        Self {
+            syntax_events: Some(vec![]),
+            ..Self::new(tokens)
         }
     }
 
     /// Tries to parse all tokens, may fail with a list of errors.
-    pub fn try_parse(mut self) -> Result<Vec<Statement>, ParserErrorReporter> {
+    pub fn try_parse(self) -> Result<Vec<Statement>, ParserErrorReporter> {
+        let (statements, error_reporter) = self.parse_with_errors();
+
+        match error_reporter.has_errors() {
+            true => Err(error_reporter),
+            false => Ok(statements),
+        }
+    }
+
+    /// Parse all tokens, returning the AST even if there are errors, for tools like editors
+    /// that still need the parts that parsed.
+    ///
+    /// What failed to parse is replaced by `StatementKind::Error` and `Expression::Error`
+    /// nodes, such an AST can't be run.
+    pub fn parse_with_errors(mut self) -> (Vec<Statement>, ParserErrorReporter) {
+        let statements = self.parse_program();
+        (statements, self.error_reporter)
+    }
+
+    /// Parse all tokens, returning the recorded `SyntaxEvent`s instead of the AST.
+    pub fn parse_syntax_events(mut self) -> (Vec<SyntaxEvent>, ParserErrorReporter) {
+        self.parse_program();
+        (self.syntax_events.unwrap_or_default(), self.error_reporter)
+    }
+
+    fn parse_program(&mut self) -> Vec<Statement> {
         let mut statements = vec![];
 
         while self.peek().is_some() {
-            let parsed_statement = self.parse_declaration();
-            match parsed_statement {
-                Some(statement) => statements.push(statement),
-                None => self.synchronize_after_error(),
-            }
+            statements.push(self.parse_declaration_or_error());
         }
 
-        match self.error_reporter.has_errors() {
-            true => Err(self.error_reporter),
-            false => Ok(statements),
+        statements
+    }
+
+    /// Where the next syntax node may start, to be wrapped later by `wrap_syntax_node`.
+    fn checkpoint(&self) -> usize {
+        self.syntax_events.as_ref().map_or(0, Vec::len)
+    }
+
+    /// Wrap everything recorded since `checkpoint` in a syntax node.
+    ///
+    /// Nodes are wrapped after being parsed, so their kind is known, and nodes left unfinished
+    /// by errors don't break the tree.
+    fn wrap_syntax_node(&mut self, checkpoint: usize, kind: SyntaxKind) {
+        if let Some(events) = &mut self.syntax_events {
+            events.insert(checkpoint, SyntaxEvent::Start(kind));
+            events.push(SyntaxEvent::Finish);
         }
     }
 
@@ -75,220 +172,1216 @@ impl<'a> Parser<'a> {
         self.error_reporter.add_parser_error(error);
     }
 
-    fn advance_token(&mut self) -> Option<&Token> {
-        self.tokens_iter.next()
+    fn advance_token(&mut self) -> Option<&'a Token> {
+        let token = self.tokens_iter.next()?;
+        self.previous = Some(token);
+        match token.token_type {
+            LeftParen => self.open_parens += 1,
+            LeftBrace => self.open_braces += 1,
+            RightParen => self.open_parens = self.open_parens.saturating_sub(1),
+            RightBrace => self.open_braces = self.open_braces.saturating_sub(1),
+            _ => {}
+        }
+        if let Some(events) = &mut self.syntax_events {
+            events.push(SyntaxEvent::Token);
+        }
+        Some(&token.token_type)
+    }
+
+    /// Whether `token` is a `)` or `}` that closes a delimiter consumed before.
+    fn closes_open_delimiter(&self, token: &Token) -> bool {
+        match token {
+            RightParen => self.open_parens > 0,
+            RightBrace => self.open_braces > 0,
+            _ => false,
+        }
+    }
+
+    fn peek(&self) -> Option<&'a Token> {
+        let token = self.tokens_iter.clone().next()?;
+        Some(&token.token_type)
+    }
+
+    /// Where the last consumed token ends.
+    fn previous_end(&self) -> Position {
+        self.previous
+            .map(|token| token.span.end)
+            .unwrap_or_default()
+    }
+
+    /// Where the next token starts, at the end of input, it's where the last token ends.
+    fn next_position(&self) -> Position {
+        match self.tokens_iter.clone().next() {
+            Some(token) => token.span.start,
+            None => self.previous_end(),
+        }
+    }
+
+    /// Span of the next token, errors about unexpected tokens point here.
+    ///
+    /// At the end of input, it's an empty span where the last token ends.
+    fn next_span(&self) -> Span {
+        match self.tokens_iter.clone().next() {
+            Some(token) => token.span,
+            None => Span::new(self.previous_end(), self.previous_end()),
+        }
     }
 
-    fn peek(&self) -> Option<&Token> {
-        self.tokens_iter.clone().next()
+    /// Span from `start` up to the end of the last consumed token.
+    fn span_from(&self, start: Position) -> Span {
+        Span::new(start, self.previous_end())
+    }
+
+    /// Consume the `;` that terminates a statement.
+    ///
+    /// When missing, the error points right after the statement, not at the next token, which
+    /// might be lines away.
+    fn consume_semicolon(&mut self) -> Option<Token> {
+        let token = self.matches(&[Semicolon]);
+
+        if token.is_none() {
+            let end = self.previous_end();
+            self.add_error(ParserError::ExpectedSemicolon(Span::new(end, end)));
+        }
+
+        token
+    }
+
+    /// Advance one token if it's the expected one, otherwise, report an error.
+    fn consume(&mut self, expected: Token) -> Option<Token> {
+        let token = self.matches(slice::from_ref(&expected));
+
+        if token.is_none() {
+            self.add_error(ParserError::ExpectedToken(expected, self.next_span()));
+        }
+
+        token
+    }
+
+    fn consume_identifier(&mut self) -> Option<Symbol> {
+        match self.peek() {
+            Some(&Token::Identifier(identifier)) => {
+                self.advance_token();
+                Some(identifier)
+            }
+            _ => {
+                self.add_error(ParserError::ExpectedIdentifier(self.next_span()));
+                None
+            }
+        }
     }
 
     /// Advance one token if matched by given slice.
     fn matches(&mut self, slice: &[Token]) -> Option<Token> {
         if let Some(peeked) = self.peek() {
             if slice.contains(peeked) {
-                return self.advance_token().cloned();
+                return self.advance_token().copied();
             }
         }
 
         None
     }
 
-    fn parse_declaration(&mut self) -> Option<Statement> {
-        if self.matches(&[Token::Var]).is_some() {
-            self.parse_var_declaration()
-        } else {
-            self.parse_statement()
+    /// Parse a declaration, if it fails, skip to where parsing can resume, and return an error
+    /// node spanning what was skipped in its place.
+    fn parse_declaration_or_error(&mut self) -> Statement {
+        let start = self.next_position();
+        let checkpoint = self.checkpoint();
+        let remaining_tokens = self.tokens_iter.len();
+
+        if let Some(statement) = self.parse_declaration() {
+            return statement;
         }
-    }
 
-    fn parse_var_declaration(&mut self) -> Option<Statement> {
-        let identifier = if let Some(Token::Identifier(identifier)) = self.peek().cloned() {
+        self.synchronize_after_error();
+        // Skip at least one token, or the same tokens would fail again, like a stray `)`.
+        if self.tokens_iter.len() == remaining_tokens {
             self.advance_token();
-            identifier
+        }
+
+        self.wrap_syntax_node(checkpoint, SyntaxKind::Error);
+        Statement::new(StatementKind::Error, self.span_from(start))
+    }
+
+    fn parse_declaration(&mut self) -> Option<Statement> {
+        let start = self.next_position();
+        let checkpoint = self.checkpoint();
+
+        let kind = if self.matches(&[Token::Class]).is_some() {
+            self.parse_class_declaration()?
+        } else if self.matches(&[Token::Fun]).is_some() {
+            self.parse_function_declaration()?
+        } else if self.matches(&[Token::Var]).is_some() {
+            self.parse_var_declaration()?
         } else {
-            panic!("report this error   'Expect VariableReference name.'");
-            // return None;
+            return self.parse_statement();
         };
 
+        self.wrap_syntax_node(checkpoint, SyntaxKind::of_statement(&kind));
+        Some(Statement::new(kind, self.span_from(start)))
+    }
+
+    fn parse_class_declaration(&mut self) -> Option<StatementKind> {
+        let name = self.consume_identifier()?;
+
+        let superclass = match self.matches(&[Less]) {
+            Some(_) => {
+                let start = self.next_position();
+                let checkpoint = self.checkpoint();
+                let superclass = self.consume_identifier()?;
+                self.wrap_syntax_node(checkpoint, SyntaxKind::VariableReference);
+                let span = self.span_from(start);
+                Some(Expression::variable_reference(superclass, span))
+            }
+            None => None,
+        };
+
+        self.consume(LeftBrace)?;
+        let mut methods = vec![];
+        while self.peek().is_some_and(|peeked| peeked != &RightBrace) {
+            let checkpoint = self.checkpoint();
+            methods.push(Rc::new(self.parse_function()?));
+            self.wrap_syntax_node(checkpoint, SyntaxKind::Function);
+        }
+        self.consume(RightBrace)?;
+
+        let declaration = ClassDeclaration::new(name, superclass, methods);
+        Some(StatementKind::Class(declaration))
+    }
+
+    fn parse_function_declaration(&mut self) -> Option<StatementKind> {
+        let declaration = self.parse_function()?;
+        Some(StatementKind::Function(Rc::new(declaration)))
+    }
+
+    /// Parse a function's name, parameters and body, shared by functions and methods.
+    fn parse_function(&mut self) -> Option<FunctionDeclaration> {
+        let start = self.next_position();
+        let name = self.consume_identifier()?;
+
+        self.consume(LeftParen)?;
+        let mut parameters = vec![];
+        if self.matches(&[RightParen]).is_none() {
+            loop {
+                let span = self.next_span();
+                parameters.push((self.consume_identifier()?, span));
+                if self.matches(&[Comma]).is_none() {
+                    break;
+                }
+            }
+            self.consume(RightParen)?;
+        }
+
+        let checkpoint = self.checkpoint();
+        self.consume(LeftBrace)?;
+        let body = self.parse_block_statements()?;
+        self.wrap_syntax_node(checkpoint, SyntaxKind::Block);
+
+        let span = self.span_from(start);
+        Some(FunctionDeclaration::new(name, parameters, body, span))
+    }
+
+    fn parse_var_declaration(&mut self) -> Option<StatementKind> {
+        let identifier = self.consume_identifier()?;
+
         let initial_value = match self.matches(&[Token::Equal]) {
             Some(_) => Some(self.parse_expression()?),
             None => None,
         };
 
-        assert_eq!(
-            self.advance_token(),
-            Some(&Token::Semicolon),
-            "expected semicolon"
-        );
+        self.consume_semicolon()?;
 
-        Some(Statement::VariableDeclaration(identifier, initial_value))
+        Some(StatementKind::VariableDeclaration(
+            identifier,
+            initial_value,
+        ))
     }
 
     fn parse_statement(&mut self) -> Option<Statement> {
-        if self.matches(&[Token::Print]).is_some() {
-            self.parse_print_statement()
+        let start = self.next_position();
+        let checkpoint = self.checkpoint();
+
+        let kind = if self.matches(&[Token::For]).is_some() {
+            let kind = self.parse_for_statement(start)?;
+            // The CST keeps the `for`, it's only desugared in the AST.
+            self.wrap_syntax_node(checkpoint, SyntaxKind::For);
+            return Some(Statement::new(kind, self.span_from(start)));
+        } else if self.matches(&[Token::If]).is_some() {
+            self.parse_if_statement()?
+        } else if self.matches(&[Token::Print]).is_some() {
+            self.parse_print_statement()?
+        } else if self.matches(&[Token::Return]).is_some() {
+            self.parse_return_statement()?
+        } else if self.matches(&[Token::While]).is_some() {
+            self.parse_while_statement()?
+        } else if self.matches(&[Token::LeftBrace]).is_some() {
+            self.parse_block()?
+        } else {
+            self.parse_expression_statement()?
+        };
+
+        self.wrap_syntax_node(checkpoint, SyntaxKind::of_statement(&kind));
+        Some(Statement::new(kind, self.span_from(start)))
+    }
+
+    /// There's no `for` node in the AST, it's desugared into a `while` loop inside of a block:
+    ///
+    /// ```txt
+    /// { initializer; while (condition) { body; increment; } }
+    /// ```
+    ///
+    /// The desugared nodes get the spans of the source they came from, the `while` spans the
+    /// whole `for` statement, starting at `start`.
+    fn parse_for_statement(&mut self, start: Position) -> Option<StatementKind> {
+        self.consume(LeftParen)?;
+
+        let initializer_start = self.next_position();
+        let checkpoint = self.checkpoint();
+        let initializer = if self.matches(&[Semicolon]).is_some() {
+            None
+        } else if self.matches(&[Var]).is_some() {
+            Some(self.parse_var_declaration()?)
         } else {
-            self.parse_expression_statement()
+            Some(self.parse_expression_statement()?)
+        };
+        if let Some(initializer) = &initializer {
+            self.wrap_syntax_node(checkpoint, SyntaxKind::of_statement(initializer));
+        }
+        let initializer = initializer
+            .map(|initializer| Statement::new(initializer, self.span_from(initializer_start)));
+
+        let condition = match self.peek() {
+            Some(Semicolon) => {
+                let span = Span::new(self.next_position(), self.next_position());
+                Expression::Literal(LiteralExpression::new(Bool(true), span))
+            }
+            _ => self.parse_expression()?,
+        };
+        self.consume(Semicolon)?;
+
+        let increment = match self.peek() {
+            Some(RightParen) => None,
+            _ => Some(self.parse_expression()?),
+        };
+        self.consume(RightParen)?;
+
+        let mut body = self.parse_statement()?;
+
+        if let Some(increment) = increment {
+            let increment_span = increment.span();
+            let increment = Statement::new(StatementKind::Expression(increment), increment_span);
+            let span = body.span;
+            body = Statement::new(StatementKind::Block(vec![body, increment]), span);
         }
+
+        let while_loop = StatementKind::While(condition, Box::new(body));
+        let while_loop = Statement::new(while_loop, self.span_from(start));
+
+        let statements = initializer.into_iter().chain([while_loop]).collect();
+        Some(StatementKind::Block(statements))
     }
 
-    fn parse_print_statement(&mut self) -> Option<Statement> {
+    fn parse_if_statement(&mut self) -> Option<StatementKind> {
+        self.consume(LeftParen)?;
+        let condition = self.parse_expression()?;
+        self.consume(RightParen)?;
+
+        let then_branch = self.parse_statement()?;
+
+        // The `else` is bound to the nearest `if` that precedes it (dangling else).
+        let else_branch = match self.matches(&[Else]) {
+            Some(_) => Some(Box::new(self.parse_statement()?)),
+            None => None,
+        };
+
+        Some(StatementKind::If(
+            condition,
+            Box::new(then_branch),
+            else_branch,
+        ))
+    }
+
+    fn parse_print_statement(&mut self) -> Option<StatementKind> {
         let expression = self.parse_expression()?;
+        self.consume_semicolon()?;
+
+        Some(StatementKind::Print(expression))
+    }
+
+    fn parse_block(&mut self) -> Option<StatementKind> {
+        self.parse_block_statements().map(StatementKind::Block)
+    }
+
+    /// Parse the statements of a block, expects the opening `{` to be already consumed.
+    fn parse_block_statements(&mut self) -> Option<Vec<Statement>> {
+        let mut statements = vec![];
+
+        while let Some(peeked) = self.peek() {
+            if peeked == &RightBrace {
+                break;
+            }
+            statements.push(self.parse_declaration_or_error());
+        }
 
-        match self.matches(&[Semicolon]) {
-            Some(_) => Some(Statement::Print(expression)),
+        match self.matches(&[RightBrace]) {
+            Some(_) => Some(statements),
             None => {
-                self.add_error(ParserError::UnterminatedStatement);
+                self.add_error(ParserError::UnclosedBlock(self.next_span()));
                 None
             }
         }
     }
 
-    fn parse_expression_statement(&mut self) -> Option<Statement> {
+    fn parse_return_statement(&mut self) -> Option<StatementKind> {
+        let value = match self.peek() {
+            Some(Semicolon) => None,
+            _ => Some(self.parse_expression()?),
+        };
+        self.consume_semicolon()?;
+
+        Some(StatementKind::Return(value))
+    }
+
+    fn parse_while_statement(&mut self) -> Option<StatementKind> {
+        self.consume(LeftParen)?;
+        let condition = self.parse_expression()?;
+        self.consume(RightParen)?;
+
+        let body = self.parse_statement()?;
+
+        Some(StatementKind::While(condition, Box::new(body)))
+    }
+
+    fn parse_expression_statement(&mut self) -> Option<StatementKind> {
         let expression = self.parse_expression()?;
+        self.consume_semicolon()?;
 
-        match self.matches(&[Semicolon]) {
-            Some(_) => Some(Statement::Expression(expression)),
-            None => {
-                self.add_error(ParserError::UnterminatedStatement);
+        Some(StatementKind::Expression(expression))
+    }
+
+    fn parse_expression(&mut self) -> Option<Expression> {
+        self.parse_precedence(Precedence::Assignment)
+    }
+
+    /// Pratt parser for expressions whose operators bind at least as tight as `precedence`.
+    ///
+    /// The first token is parsed by its prefix parselet, then, each following operator that
+    /// binds tight enough is parsed by its infix or postfix parselet, see `Parser::parse_rule`.
+    fn parse_precedence(&mut self, precedence: Precedence) -> Option<Expression> {
+        let start = self.next_position();
+        let checkpoint = self.checkpoint();
+
+        let prefix = self.peek().and_then(|token| Self::parse_rule(token).prefix);
+        let mut expression = match prefix {
+            Some(prefix) => {
+                let token = *self.advance_token()?;
+                prefix(self, token, start)?
+            }
+            None => self.parse_missing_expression()?,
+        };
+        self.wrap_syntax_node(checkpoint, SyntaxKind::of_expression(&expression));
+
+        while let Some(rule) = self.peek().and_then(|token| Self::parse_rule(token).infix) {
+            if rule.precedence < precedence {
+                break;
+            }
+            let operator = *self.advance_token()?;
+
+            expression = match rule.parselet {
+                InfixParselet::Infix(parselet) => {
+                    // Operators of the same precedence on the right are part of the right operand
+                    // only if right-associative, as in `a = (b = c)`, versus `(a - b) - c`.
+                    let right_precedence = match rule.associativity {
+                        Associativity::Left => rule.precedence.tighter(),
+                        Associativity::Right => rule.precedence,
+                    };
+                    let right = self.parse_precedence(right_precedence)?;
+                    parselet(self, expression, operator, right)?
+                }
+                InfixParselet::Postfix(parselet) => parselet(self, expression, operator)?,
+            };
+            self.wrap_syntax_node(checkpoint, SyntaxKind::of_expression(&expression));
+        }
+
+        Some(expression)
+    }
+
+    /// Parselets of each token, all operators are registered here.
+    ///
+    /// Prefix parselets parse the expressions starting with their token, infix and postfix ones
+    /// continue an expression that comes before their token.
+    fn parse_rule(token: &Token) -> ParseRule<'a> {
+        use Associativity::{Left, Right};
+        use InfixParselet::{Infix, Postfix};
+        use Precedence::*;
+
+        match token {
+            LeftParen => {
+                ParseRule::prefix(Self::parse_grouping).with_infix(
+                    Postfix(Self::finish_call),
+                    Call,
+                    Left,
+                )
+            }
+            Dot => ParseRule::infix(Postfix(Self::finish_property), Call, Left),
+            Minus => {
+                ParseRule::prefix(Self::parse_unary).with_infix(
+                    Infix(Self::finish_binary),
+                    Term,
+                    Left,
+                )
+            }
+            Bang => ParseRule::prefix(Self::parse_unary),
+            Plus => ParseRule::infix(Infix(Self::finish_binary), Term, Left),
+            Slash | Star => ParseRule::infix(Infix(Self::finish_binary), Factor, Left),
+            BangEqual | EqualEqual => ParseRule::infix(Infix(Self::finish_binary), Equality, Left),
+            Greater | GreaterEqual | Less | LessEqual => {
+                ParseRule::infix(Infix(Self::finish_binary), Comparison, Left)
+            }
+            Token::And => ParseRule::infix(Infix(Self::finish_logical), Precedence::And, Left),
+            Token::Or => ParseRule::infix(Infix(Self::finish_logical), Precedence::Or, Left),
+            Question => ParseRule::infix(Postfix(Self::finish_conditional), Conditional, Right),
+            Equal => ParseRule::infix(Infix(Self::finish_assignment), Assignment, Right),
+            Identifier(_) => ParseRule::prefix(Self::parse_variable_reference),
+            This => ParseRule::prefix(Self::parse_this),
+            Super => ParseRule::prefix(Self::parse_super),
+            Interpolation(_) => ParseRule::prefix(Self::parse_interpolation),
+            token if token.is_literal() => ParseRule::prefix(Self::parse_literal),
+            _ => ParseRule::NONE,
+        }
+    }
+
+    /// Report a token that can't start an expression.
+    fn parse_missing_expression(&mut self) -> Option<Expression> {
+        let start = self.next_position();
+        let Some(&token) = self.peek() else {
+            self.add_error(ParserError::UnexpectedEof(self.next_span()));
+            return None;
+        };
+        self.add_error(ParserError::ExpectedValidExpression(
+            token,
+            self.next_span(),
+        ));
+
+        match token {
+            // The expression is missing, as in `print ;`, `f(1, )` or `"${1 + }"`, parsing can go
+            // on with an error node in its place.
+            Semicolon | InterpolationMiddle(_) | InterpolationEnd(_) => {
+                Some(Expression::Error(Span::new(start, start)))
+            }
+            RightParen if self.closes_open_delimiter(&token) => {
+                Some(Expression::Error(Span::new(start, start)))
+            }
+            // Left for the block it closes.
+            RightBrace if self.closes_open_delimiter(&token) => None,
+            // Anything else is skipped, including stray closing delimiters, as in `print ) ;`,
+            // so they're only reported once.
+            _ => {
+                self.advance_token();
                 None
             }
         }
     }
 
-    fn parse_expression(&mut self) -> Option<Expression> {
-        self.equality()
-    }
-
-    // Helper function to build binary expression parser steps in this form:
-    // step  →   next_step ( TOKENS next_step )* ;
-    //
-    // Form is satisfied for the following grammar rules:
-    // equality       → comparison ( ( "!=" | "=="             ) comparison )* ;
-    // comparison     → term       ( ( ">" | ">=" | "<" | "<=" ) term       )* ;
-    // term           → factor     ( ( "-" | "+"               ) factor     )* ;
-    // factor         → unary      ( ( "/" | "*"               ) unary      )* ;
-    fn parse_binary_expression_by<F>(
-        &mut self,
-        next_step: F,
-        tokens: &[Token],
-    ) -> Option<Expression>
-    where
-        F: Fn(&mut Self) -> Option<Expression>,
-    {
-        let mut expr = next_step(self)?;
+    fn parse_unary(&mut self, operator: Token, start: Position) -> Option<Expression> {
+        let expression = self.parse_precedence(Precedence::Unary)?;
+        let span = self.span_from(start);
+        Some(Expression::Unary(Box::new(UnaryExpression::new(
+            operator, expression, span,
+        ))))
+    }
 
-        while let Some(operator) = self.matches(tokens) {
-            let right = next_step(self)?;
-            expr = Expression::Binary(box BinaryExpression::new(expr, operator, right));
+    fn parse_grouping(&mut self, _: Token, start: Position) -> Option<Expression> {
+        let opening = self.span_from(start);
+        // Eat next expression
+        let Some(expr) = self.parse_expression() else {
+            return self.recover_at_right_paren(start);
+        };
+
+        // We expect the next token to be a closing parenthesis
+        // If it's not, enter recovery mode that jumps to the next statement.
+        match self.matches(&[RightParen]) {
+            Some(_) => Some(Expression::Grouping(Box::new(expr), self.span_from(start))),
+            None => {
+                self.add_error(ParserError::UnclosedGrouping(opening, self.next_span()));
+                None
+            }
         }
+    }
 
-        Some(expr)
+    fn parse_variable_reference(&mut self, token: Token, start: Position) -> Option<Expression> {
+        let Token::Identifier(identifier) = token else {
+            unreachable!("only registered for identifiers")
+        };
+        let span = self.span_from(start);
+        Some(Expression::variable_reference(identifier, span))
     }
 
-    fn equality(&mut self) -> Option<Expression> {
-        self.parse_binary_expression_by(Self::comparison, &[BangEqual, EqualEqual])
+    fn parse_this(&mut self, _: Token, start: Position) -> Option<Expression> {
+        Some(Expression::This(
+            ScopeDepth::default(),
+            self.span_from(start),
+        ))
     }
 
-    fn comparison(&mut self) -> Option<Expression> {
-        self.parse_binary_expression_by(Self::term, &[Greater, GreaterEqual, Less, LessEqual])
+    fn parse_super(&mut self, _: Token, start: Position) -> Option<Expression> {
+        self.consume(Dot)?;
+        let method = self.consume_identifier()?;
+        let span = self.span_from(start);
+        Some(Expression::Super(SuperExpression::new(method, span)))
     }
 
-    fn term(&mut self) -> Option<Expression> {
-        self.parse_binary_expression_by(Self::factor, &[Minus, Plus])
+    fn parse_literal(&mut self, token: Token, start: Position) -> Option<Expression> {
+        let span = self.span_from(start);
+        Some(Expression::Literal(LiteralExpression::new(token, span)))
     }
 
-    fn factor(&mut self) -> Option<Expression> {
-        self.parse_binary_expression_by(Self::parse_unary_expression, &[Slash, Star])
+    fn finish_binary(
+        &mut self,
+        left: Expression,
+        operator: Token,
+        right: Expression,
+    ) -> Option<Expression> {
+        Some(Expression::Binary(Box::new(BinaryExpression::new(
+            left, operator, right,
+        ))))
     }
 
-    fn parse_unary_expression(&mut self) -> Option<Expression> {
-        if let Some(operator) = self.matches(&[Bang, Minus]) {
-            let expression = self.parse_unary_expression()?;
-            Some(Expression::Unary(box UnaryExpression::new(
-                operator, expression,
-            )))
-        } else {
-            self.parse_primary()
-        }
+    fn finish_logical(
+        &mut self,
+        left: Expression,
+        operator: Token,
+        right: Expression,
+    ) -> Option<Expression> {
+        Some(Expression::Logical(Box::new(LogicalExpression::new(
+            left, operator, right,
+        ))))
     }
 
-    fn parse_primary(&mut self) -> Option<Expression> {
-        let token = match self.tokens_iter.next() {
-            Some(token) => token,
-            None => todo!("expected something, found EOF"),
-        };
+    // The branch between "?" and ":" is delimited by them, so it can be any expression, like in C.
+    fn finish_conditional(&mut self, condition: Expression, _: Token) -> Option<Expression> {
+        let then_branch = self.parse_expression()?;
+        self.consume(Colon)?;
+        let else_branch = self.parse_precedence(Precedence::Conditional)?;
+        let conditional = ConditionalExpression::new(condition, then_branch, else_branch);
+        Some(Expression::Conditional(Box::new(conditional)))
+    }
 
-        if let Token::Identifier(identifier) = token {
-            Some(Expression::VariableReference(identifier.clone()))
-        } else if token.is_literal() {
-            Some(Expression::Literal(LiteralExpression::new(token.clone())))
-        } else if token == &Token::LeftParen {
-            // Eat next expression
-            let expr = self.parse_expression()?;
-
-            // We expect the next token to be a closing parenthesis
-            // If it's not, enter recovery mode that jumps to the next statement.
-            match self.matches(&[RightParen]) {
-                Some(_) => Some(Expression::Grouping(box expr)),
-                None => {
-                    self.add_error(ParserError::UnclosedGrouping);
-                    None
+    // Assignment is right-associative, so the target is parsed as a regular expression first,
+    // and only after seeing the "=" we check if it's a valid l-value.
+    fn finish_assignment(
+        &mut self,
+        target: Expression,
+        _: Token,
+        value: Expression,
+    ) -> Option<Expression> {
+        match target {
+            Expression::VariableReference(identifier, _, span) => {
+                let span = span.to(value.span());
+                let assign = AssignExpression::new(identifier, value, span);
+                Some(Expression::Assign(Box::new(assign)))
+            }
+            Expression::Get(get) => {
+                let GetExpression { object, name, .. } = *get;
+                let set = SetExpression::new(object, name, value);
+                Some(Expression::Set(Box::new(set)))
+            }
+            _ => {
+                let span = target.span();
+                self.add_error(ParserError::InvalidAssignmentTarget(span));
+                None
+            }
+        }
+    }
+
+    fn finish_call(&mut self, callee: Expression, _: Token) -> Option<Expression> {
+        let mut arguments = vec![];
+        if self.matches(&[RightParen]).is_none() {
+            loop {
+                let Some(argument) = self.parse_expression() else {
+                    return self.recover_at_right_paren(callee.span().start);
+                };
+                arguments.push(argument);
+                if self.matches(&[Comma]).is_none() {
+                    break;
                 }
             }
-        } else {
-            self.add_error(ParserError::ExpectedValidExpression(token.clone()));
-            None
+            self.consume(RightParen)?;
         }
+
+        let span = Span::new(callee.span().start, self.previous_end());
+        Some(Expression::Call(Box::new(CallExpression::new(
+            callee, arguments, span,
+        ))))
     }
 
-    fn synchronize_after_error(&mut self) {
+    fn finish_property(&mut self, object: Expression, _: Token) -> Option<Expression> {
+        let name = self.consume_identifier()?;
+        let span = Span::new(object.span().start, self.previous_end());
+        Some(Expression::Get(Box::new(GetExpression::new(
+            object, name, span,
+        ))))
+    }
+
+    /// Parse the rest of an interpolated string, its first segment is `token`.
+    fn parse_interpolation(&mut self, token: Token, start: Position) -> Option<Expression> {
+        let Token::Interpolation(first_segment) = token else {
+            unreachable!("only registered for interpolations")
+        };
+        let first_segment =
+            LiteralExpression::new(Token::String(first_segment), self.span_from(start));
+        let mut parts = vec![Expression::Literal(first_segment)];
+
         loop {
-            let skipped = self.advance_token().cloned();
-            let peeked = self.peek();
+            let expression = match self.peek() {
+                // Nothing between the braces, as in `"${}"`.
+                Some(InterpolationMiddle(_) | InterpolationEnd(_)) => {
+                    let span = Span::new(self.previous_end(), self.next_position());
+                    self.add_error(ParserError::EmptyInterpolation(span));
+                    Expression::Error(span)
+                }
+                _ => self.parse_expression()?,
+            };
+            parts.push(expression);
 
-            // If has reached the end of the tokens_iter, or an semicolon
-            if let None | Some(Token::Semicolon) = skipped {
-                return;
-            }
-            if peeked.is_none() {
-                return;
+            let segment_start = self.next_position();
+            let (segment, is_last) = match self.peek() {
+                Some(&InterpolationMiddle(segment)) => (segment, false),
+                Some(&InterpolationEnd(segment)) => (segment, true),
+                _ => {
+                    self.add_error(ParserError::UnclosedInterpolation(self.next_span()));
+                    return None;
+                }
+            };
+            self.advance_token();
+
+            let span = self.span_from(segment_start);
+            let segment = LiteralExpression::new(Token::String(segment), span);
+            parts.push(Expression::Literal(segment));
+
+            if is_last {
+                break;
             }
+        }
+
+        let interpolation = InterpolationExpression::new(parts, self.span_from(start));
+        Some(Expression::Interpolation(interpolation))
+    }
 
-            // If matches any token that are the start of an statement,
-            // then consider this to be synchronized
-            if peeked.map(Token::is_start_of_statement).unwrap_or(false) {
-                return;
+    /// Skip tokens after an error, up to where parsing can resume.
+    ///
+    /// That's right after a `;`, or before a keyword that starts a statement, or before a `}` or
+    /// `)`, which closes a delimiter opened before the error. Delimiters opened while skipping
+    /// are skipped along with everything inside of them, and so are stray closing delimiters.
+    fn synchronize_after_error(&mut self) {
+        let mut depth = 0_usize;
+
+        while let Some(peeked) = self.peek() {
+            match peeked {
+                LeftParen | LeftBrace => depth += 1,
+                RightParen | RightBrace if depth > 0 => depth -= 1,
+                token @ (RightParen | RightBrace) if self.closes_open_delimiter(token) => return,
+                Semicolon if depth == 0 => {
+                    self.advance_token();
+                    return;
+                }
+                token if depth == 0 && token.is_start_of_statement() => return,
+                _ => {}
             }
 
             self.advance_token();
         }
     }
+
+    /// After an error inside of parentheses, skip to the `)` that closes them, and return an
+    /// error node from `start` up to it, so the rest of the expression can still be parsed.
+    fn recover_at_right_paren(&mut self, start: Position) -> Option<Expression> {
+        self.synchronize_after_error();
+        self.matches(&[RightParen])?;
+        Some(Expression::Error(self.span_from(start)))
+    }
+}
+
+/// How tightly operators bind their operands, from the loosest to the tightest.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Precedence {
+    Assignment,
+    Conditional,
+    Or,
+    And,
+    Equality,
+    Comparison,
+    Term,
+    Factor,
+    Unary,
+    Call,
+}
+
+impl Precedence {
+    /// The next level, left-associative operators parse their right operand with it.
+    fn tighter(self) -> Self {
+        match self {
+            Self::Assignment => Self::Conditional,
+            Self::Conditional => Self::Or,
+            Self::Or => Self::And,
+            Self::And => Self::Equality,
+            Self::Equality => Self::Comparison,
+            Self::Comparison => Self::Term,
+            Self::Term => Self::Factor,
+            Self::Factor => Self::Unary,
+            Self::Unary | Self::Call => Self::Call,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Associativity {
+    Left,
+    Right,
+}
+
+/// Parses an expression starting with a token, which was already consumed, and starts at the
+/// given position.
+type PrefixParselet<'a> = fn(&mut Parser<'a>, Token, Position) -> Option<Expression>;
+
+#[derive(Copy, Clone)]
+enum InfixParselet<'a> {
+    /// Builds an expression out of its operands, the right one is parsed before calling it.
+    Infix(fn(&mut Parser<'a>, Expression, Token, Expression) -> Option<Expression>),
+    /// Parses what comes after its token by itself, like the arguments of a call.
+    Postfix(fn(&mut Parser<'a>, Expression, Token) -> Option<Expression>),
+}
+
+#[derive(Copy, Clone)]
+struct InfixRule<'a> {
+    parselet: InfixParselet<'a>,
+    precedence: Precedence,
+    associativity: Associativity,
+}
+
+/// Entry of `Parser::parse_rule`, with the parselets of a token.
+#[derive(Copy, Clone)]
+struct ParseRule<'a> {
+    prefix: Option<PrefixParselet<'a>>,
+    infix: Option<InfixRule<'a>>,
+}
+
+impl<'a> ParseRule<'a> {
+    const NONE: Self = Self {
+        prefix: None,
+        infix: None,
+    };
+
+    fn prefix(parselet: PrefixParselet<'a>) -> Self {
+        Self {
+            prefix: Some(parselet),
+            ..Self::NONE
+        }
+    }
+
+    fn infix(
+        parselet: InfixParselet<'a>,
+        precedence: Precedence,
+        associativity: Associativity,
+    ) -> Self {
+        Self::NONE.with_infix(parselet, precedence, associativity)
+    }
+
+    fn with_infix(
+        self,
+        parselet: InfixParselet<'a>,
+        precedence: Precedence,
+        associativity: Associativity,
+    ) -> Self {
+        let infix = InfixRule {
+            parselet,
+            precedence,
+            associativity,
+        };
+        Self {
+            infix: Some(infix),
+            ..self
+        }
+    }
 }
 
 #[derive(thiserror::Error, Debug)]
 pub enum ParserError {
+    /// Spans of the `(` and of what was found instead of the `)`.
     #[error("Unclosed delimiter, expected ')'")]
-    UnclosedGrouping,
+    UnclosedGrouping(Span, Span),
+    #[error("Unclosed block, expected '}}'")]
+    UnclosedBlock(Span),
+    #[error("Unclosed interpolation, expected '}}'")]
+    UnclosedInterpolation(Span),
+    #[error("Empty interpolation, expected an expression inside of '${{}}'")]
+    EmptyInterpolation(Span),
     #[error("Unterminated statement, expected ';'")]
-    UnterminatedStatement,
+    ExpectedSemicolon(Span),
+    #[error("Unexpected end of input, expected an expression")]
+    UnexpectedEof(Span),
     #[error("Expected valid expression, found {0:?}")]
-    ExpectedValidExpression(Token),
+    ExpectedValidExpression(Token, Span),
+    #[error("Expected '{0}'")]
+    ExpectedToken(Token, Span),
+    #[error("Expected identifier")]
+    ExpectedIdentifier(Span),
+    #[error("Invalid assignment target")]
+    InvalidAssignmentTarget(Span),
+}
+
+impl ParserError {
+    pub fn span(&self) -> Span {
+        match *self {
+            Self::UnclosedGrouping(_, span)
+            | Self::UnclosedBlock(span)
+            | Self::UnclosedInterpolation(span)
+            | Self::EmptyInterpolation(span)
+            | Self::ExpectedSemicolon(span)
+            | Self::UnexpectedEof(span)
+            | Self::ExpectedValidExpression(_, span)
+            | Self::ExpectedToken(_, span)
+            | Self::ExpectedIdentifier(span)
+            | Self::InvalidAssignmentTarget(span) => span,
+        }
+    }
+
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        let diagnostic = Diagnostic::error("Parser", self.to_string(), self.span());
+
+        match *self {
+            Self::UnclosedGrouping(opening, _) => {
+                diagnostic.with_secondary_label(opening, "unclosed delimiter")
+            }
+            Self::ExpectedSemicolon(_) => diagnostic.with_label("add a ';' here"),
+            Self::EmptyInterpolation(_) => diagnostic.with_label("expected an expression here"),
+            Self::InvalidAssignmentTarget(_) => {
+                diagnostic.with_help("only variables and properties can be assigned to")
+            }
+            _ => diagnostic,
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::Scanner;
+    use crate::lexer::Scanner;
 
     #[test]
     fn test_parsing_expression() {
         let source_code = "1 - (2 * 3) < 4 == false";
-        let scanner = Scanner::new(source_code);
+        let mut scanner = Scanner::new(source_code);
         let tokens = scanner.try_scan_all().unwrap();
 
         let ast = Parser::new(&tokens).parse_expression().unwrap();
         assert_eq!("(== (< (- 1 (group (* 2 3))) 4) false)", ast.to_string());
     }
+
+    #[test]
+    fn test_parsing_operators_by_precedence_and_associativity() {
+        let source_code = "a.b = -c(1) - 2 - 3 * 4 / 5 or !d and e != f";
+        let tokens = Scanner::new(source_code).try_scan_all().unwrap();
+
+        let ast = Parser::new(&tokens).parse_expression().unwrap();
+        assert_eq!(
+            ast.to_string(),
+            "(= (. (value_of a) b) (or (- (- (- (call (value_of c) 1)) 2) (/ (* 3 4) 5)) \
+             (and (! (value_of d)) (!= (value_of e) (value_of f)))))"
+        );
+    }
+
+    #[test]
+    fn test_parsing_conditionals_as_right_associative() {
+        let source_code = "a = b or c ? d = 1 : e ? 2 : f";
+        let tokens = Scanner::new(source_code).try_scan_all().unwrap();
+
+        let ast = Parser::new(&tokens).parse_expression().unwrap();
+        assert_eq!(
+            ast.to_string(),
+            "(= a (?: (or (value_of b) (value_of c)) (= d 1) \
+             (?: (value_of e) 2 (value_of f))))"
+        );
+    }
+
+    #[test]
+    fn test_parsing_nested_blocks() {
+        let source_code = "var a = 1; { var a = 2; { print a; } }";
+        let tokens = Scanner::new(source_code).try_scan_all().unwrap();
+
+        let statements = Parser::new(&tokens).try_parse().unwrap();
+        let statements: Vec<_> = statements.iter().map(ToString::to_string).collect();
+
+        assert_eq!(
+            statements,
+            [
+                "(var a (1))",
+                "(block (var a (2)) (block (print (value_of a))))"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parsing_assignment_is_right_associative() {
+        let source_code = "a = b = 1 + 2";
+        let tokens = Scanner::new(source_code).try_scan_all().unwrap();
+
+        let ast = Parser::new(&tokens).parse_expression().unwrap();
+        assert_eq!("(= a (= b (+ 1 2)))", ast.to_string());
+    }
+
+    #[test]
+    fn test_parsing_logical_operators_precedence() {
+        let source_code = "a or b and c == d";
+        let tokens = Scanner::new(source_code).try_scan_all().unwrap();
+
+        let ast = Parser::new(&tokens).parse_expression().unwrap();
+        assert_eq!(
+            "(or (value_of a) (and (value_of b) (== (value_of c) (value_of d))))",
+            ast.to_string()
+        );
+    }
+
+    #[test]
+    fn test_invalid_assignment_target_error() {
+        let source_code = "a + b = c;";
+        let tokens = Scanner::new(source_code).try_scan_all().unwrap();
+
+        let error_reporter = Parser::new(&tokens).try_parse().unwrap_err();
+        let [error] = error_reporter.errors() else {
+            panic!("expected a single error, found {error_reporter}");
+        };
+
+        assert!(matches!(error, ParserError::InvalidAssignmentTarget(_)));
+        assert_eq!(&source_code[error.span().byte_range()], "a + b");
+    }
+
+    #[test]
+    fn test_parsing_dangling_else() {
+        let source_code = "if (a) if (b) print 1; else print 2;";
+        let tokens = Scanner::new(source_code).try_scan_all().unwrap();
+
+        let statements = Parser::new(&tokens).try_parse().unwrap();
+        assert_eq!(
+            statements[0].to_string(),
+            "(if (value_of a) (if (value_of b) (print 1) (print 2)))"
+        );
+    }
+
+    #[test]
+    fn test_for_is_desugared_into_while() {
+        let source_code = "for (var i = 0; i < 3; i = i + 1) print i;";
+        let tokens = Scanner::new(source_code).try_scan_all().unwrap();
+
+        let statements = Parser::new(&tokens).try_parse().unwrap();
+        assert_eq!(
+            statements[0].to_string(),
+            "(block (var i (0)) (while (< (value_of i) 3) \
+             (block (print (value_of i)) (expression (= i (+ (value_of i) 1))))))"
+        );
+    }
+
+    #[test]
+    fn test_parsing_function_declaration_and_calls() {
+        let source_code = "fun add(a, b) { return a + b; } add(1, 2)(3)();";
+        let tokens = Scanner::new(source_code).try_scan_all().unwrap();
+
+        let statements = Parser::new(&tokens).try_parse().unwrap();
+        let statements: Vec<_> = statements.iter().map(ToString::to_string).collect();
+
+        assert_eq!(
+            statements,
+            [
+                "(fun add (a b) (return (+ (value_of a) (value_of b))))",
+                "(expression (call (call (call (value_of add) 1 2) 3)))"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parsing_class_with_property_access() {
+        let source_code = "class A { get() { return this.x; } } a.b.c = a.get();";
+        let tokens = Scanner::new(source_code).try_scan_all().unwrap();
+
+        let statements = Parser::new(&tokens).try_parse().unwrap();
+        let statements: Vec<_> = statements.iter().map(ToString::to_string).collect();
+
+        assert_eq!(
+            statements,
+            [
+                "(class A (fun get () (return (. this x))))",
+                "(expression (= (. (. (value_of a) b) c) (call (. (value_of a) get))))"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parsing_subclass_with_super_call() {
+        let source_code = "class B < A { f() { super.f(); } }";
+        let tokens = Scanner::new(source_code).try_scan_all().unwrap();
+
+        let statements = Parser::new(&tokens).try_parse().unwrap();
+        assert_eq!(
+            statements[0].to_string(),
+            "(class B (< (value_of A)) (fun f () (expression (call (super f)))))"
+        );
+    }
+
+    #[test]
+    fn test_nodes_keep_their_source_spans() {
+        let source_code = "var a;\nprint a.b(1,\n  2);";
+        let tokens = Scanner::new(source_code).try_scan_all().unwrap();
+
+        let statements = Parser::new(&tokens).try_parse().unwrap();
+        let StatementKind::Print(call) = &statements[1].kind else {
+            panic!()
+        };
+
+        // Spans end right after their last token.
+        assert_eq!(
+            statements[1].span,
+            Span::new(Position::new(2, 1, 7), Position::new(3, 6, 25))
+        );
+        assert_eq!(
+            call.span(),
+            Span::new(Position::new(2, 7, 13), Position::new(3, 5, 24))
+        );
+    }
+
+    #[test]
+    fn test_parsing_string_interpolation() {
+        let source_code = r#""Hello ${name}, you have ${count + 1} ${"item${"s"}"}""#;
+        let tokens = Scanner::new(source_code).try_scan_all().unwrap();
+
+        let ast = Parser::new(&tokens).parse_expression().unwrap();
+        assert_eq!(
+            ast.to_string(),
+            r#"(interpolate "Hello " (value_of name) ", you have " (+ (value_of count) 1) " " (interpolate "item" "s" "") "")"#
+        );
+    }
+
+    #[test]
+    fn test_unclosed_block_error() {
+        let source_code = "{ var a = 2;";
+        let tokens = Scanner::new(source_code).try_scan_all().unwrap();
+
+        assert!(Parser::new(&tokens).try_parse().is_err());
+    }
+
+    #[test]
+    fn test_syntax_errors_are_reported_instead_of_panicking() {
+        let source_code = "var = 1; var b = 2 print b; var c = 3; print 1 +";
+        let tokens = Scanner::new(source_code).try_scan_all().unwrap();
+
+        let error_reporter = Parser::new(&tokens).try_parse().unwrap_err();
+        let errors: Vec<_> = error_reporter
+            .errors()
+            .iter()
+            .map(|error| format!("[{}] {error}", error.span().start))
+            .collect();
+
+        assert_eq!(
+            errors,
+            [
+                "[1:5] Expected identifier",
+                "[1:19] Unterminated statement, expected ';'",
+                "[1:49] Unexpected end of input, expected an expression",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parsing_with_errors_keeps_the_rest_of_the_tree() {
+        let source_code = "\
+            var a = 1;\n\
+            fun f() { print a +; var = 2 }\n\
+            print (1 + * 2) + f(1, );\n\
+            class C {}}\n\
+            print a;";
+        let tokens = Scanner::new(source_code).try_scan_all().unwrap();
+
+        let (statements, error_reporter) = Parser::new(&tokens).parse_with_errors();
+        let statements: Vec<_> = statements.iter().map(ToString::to_string).collect();
+        let errors: Vec<_> = error_reporter
+            .errors()
+            .iter()
+            .map(|error| format!("[{}] {error}", error.span().start))
+            .collect();
+
+        assert_eq!(
+            statements,
+            [
+                "(var a (1))",
+                "(fun f () (print (+ (value_of a) (error))) (error))",
+                "(print (+ (error) (call (value_of f) 1 (error))))",
+                "(class C)",
+                "(error)",
+                "(print (value_of a))",
+            ]
+        );
+        assert_eq!(
+            errors,
+            [
+                "[2:20] Expected valid expression, found Semicolon",
+                "[2:26] Expected identifier",
+                "[3:12] Expected valid expression, found Star",
+                "[3:24] Expected valid expression, found RightParen",
+                "[4:11] Expected valid expression, found RightBrace",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_empty_interpolation_error() {
+        let source_code = r#"print "a${}" "b"; print "${1 + } c${2}";"#;
+        let tokens = Scanner::new(source_code).try_scan_all().unwrap();
+
+        let (statements, error_reporter) = Parser::new(&tokens).parse_with_errors();
+        let statements: Vec<_> = statements.iter().map(ToString::to_string).collect();
+        let errors: Vec<_> = error_reporter
+            .errors()
+            .iter()
+            .map(|error| format!("[{}] {error}", error.span().start))
+            .collect();
+
+        assert_eq!(
+            statements,
+            [
+                "(error)",
+                r#"(print (interpolate "" (+ 1 (error)) " c" 2 ""))"#,
+            ]
+        );
+        assert_eq!(
+            errors,
+            [
+                "[1:11] Empty interpolation, expected an expression inside of '${}'",
+                "[1:13] Unterminated statement, expected ';'",
+                r#"[1:32] Expected valid expression, found InterpolationMiddle(" c")"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stray_closing_delimiters_are_reported_once() {
+        for (source_code, expected_statements) in [
+            ("print ) ;", vec!["(error)"]),
+            (
+                "{ print ) ; var x = 1; } print 1;",
+                vec!["(block (error) (var x (1)))", "(print 1)"],
+            ),
+        ] {
+            let tokens = Scanner::new(source_code).try_scan_all().unwrap();
+
+            let (statements, error_reporter) = Parser::new(&tokens).parse_with_errors();
+            let statements: Vec<_> = statements.iter().map(ToString::to_string).collect();
+            let errors: Vec<_> = error_reporter
+                .errors()
+                .iter()
+                .map(ToString::to_string)
+                .collect();
+
+            assert_eq!(statements, expected_statements);
+            assert_eq!(errors, ["Expected valid expression, found RightParen"]);
+        }
+    }
 }
@@ -0,0 +1,462 @@
+//! Static pass that runs between the parser and the interpreter.
+//!
+//! For every variable usage, it computes how many scopes away the variable was declared, so the
+//! interpreter doesn't need to search for it. It also reports semantic errors that don't need to
+//! wait for the program to run.
+
+use std::{collections::HashMap, mem};
+
+use crate::{
+    diagnostic::Diagnostic,
+    expression::{
+        AssignExpression, BinaryExpression, CallExpression, ConditionalExpression, Expression,
+        GetExpression, InterpolationExpression, LogicalExpression, ScopeDepth, SetExpression,
+        SuperExpression, UnaryExpression,
+    },
+    statement::{ClassDeclaration, FunctionDeclaration, Statement, StatementKind},
+    symbol::Symbol,
+    token::Span,
+};
+
+pub fn resolve_program(statements: &[Statement]) -> Result<(), ResolverError> {
+    let mut resolver = Resolver::default();
+    resolve_statements(statements, &mut resolver)
+}
+
+fn resolve_statements(
+    statements: &[Statement],
+    resolver: &mut Resolver,
+) -> Result<(), ResolverError> {
+    statements
+        .iter()
+        .try_for_each(|statement| statement.resolve(resolver))
+}
+
+#[derive(Default)]
+pub struct Resolver {
+    /// Stack of local scopes, global variables aren't tracked.
+    ///
+    /// Each variable maps to whether its initializer was already resolved.
+    scopes: Vec<HashMap<Symbol, bool>>,
+    current_function: FunctionKind,
+    current_class: ClassKind,
+}
+
+#[derive(Default, Clone, Copy, PartialEq)]
+enum FunctionKind {
+    #[default]
+    None,
+    Function,
+    Method,
+    Initializer,
+}
+
+#[derive(Default, Clone, Copy, PartialEq)]
+enum ClassKind {
+    #[default]
+    None,
+    Class,
+    Subclass,
+}
+
+impl Resolver {
+    fn begin_scope(&mut self) {
+        self.scopes.push(HashMap::new());
+    }
+
+    fn end_scope(&mut self) {
+        self.scopes.pop();
+    }
+
+    /// Add a variable to the innermost scope, marked as not ready to be read yet.
+    fn declare(&mut self, identifier: Symbol, span: Span) -> Result<(), ResolverError> {
+        let Some(scope) = self.scopes.last_mut() else {
+            return Ok(());
+        };
+
+        if scope.contains_key(&identifier) {
+            return Err(ResolverError::AlreadyDeclared(identifier, span));
+        }
+
+        scope.insert(identifier, false);
+        Ok(())
+    }
+
+    /// Mark a declared variable as ready to be read.
+    fn define(&mut self, identifier: Symbol) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(identifier, true);
+        }
+    }
+
+    /// Store the distance to the innermost scope declaring this variable, if any.
+    fn resolve_local(&mut self, identifier: Symbol, depth: &ScopeDepth) {
+        let found = self
+            .scopes
+            .iter()
+            .rev()
+            .position(|scope| scope.contains_key(&identifier));
+
+        if let Some(found) = found {
+            depth.set(found);
+        }
+    }
+
+    /// Parameters and body share the same scope, matching how functions are called.
+    fn resolve_function(
+        &mut self,
+        declaration: &FunctionDeclaration,
+        kind: FunctionKind,
+    ) -> Result<(), ResolverError> {
+        let enclosing_function = mem::replace(&mut self.current_function, kind);
+        self.begin_scope();
+
+        let result = declaration
+            .parameters
+            .iter()
+            .try_for_each(|&(parameter, span)| {
+                self.declare(parameter, span)?;
+                self.define(parameter);
+                Ok(())
+            })
+            .and_then(|()| resolve_statements(&declaration.body, self));
+
+        self.end_scope();
+        self.current_function = enclosing_function;
+        result
+    }
+
+    fn resolve_class(
+        &mut self,
+        declaration: &ClassDeclaration,
+        span: Span,
+    ) -> Result<(), ResolverError> {
+        let ClassDeclaration {
+            name,
+            superclass,
+            methods,
+        } = declaration;
+
+        self.declare(*name, span)?;
+        self.define(*name);
+
+        if let Some(superclass) = superclass {
+            superclass.resolve(self)?;
+
+            self.current_class = ClassKind::Subclass;
+            self.begin_scope();
+            self.define(Symbol::SUPER);
+        }
+
+        self.begin_scope();
+        self.define(Symbol::THIS);
+
+        let result = methods.iter().try_for_each(|method| {
+            let kind = match method.name {
+                Symbol::INIT => FunctionKind::Initializer,
+                _ => FunctionKind::Method,
+            };
+            self.resolve_function(method, kind)
+        });
+
+        self.end_scope();
+        if superclass.is_some() {
+            self.end_scope();
+        }
+
+        result
+    }
+}
+
+pub trait Resolve {
+    fn resolve(&self, resolver: &mut Resolver) -> Result<(), ResolverError>;
+}
+
+impl Resolve for Statement {
+    fn resolve(&self, resolver: &mut Resolver) -> Result<(), ResolverError> {
+        let span = self.span;
+
+        match &self.kind {
+            StatementKind::Block(statements) => {
+                resolver.begin_scope();
+                let result = resolve_statements(statements, resolver);
+                resolver.end_scope();
+
+                result
+            }
+            StatementKind::Class(declaration) => {
+                let enclosing_class = mem::replace(&mut resolver.current_class, ClassKind::Class);
+                let result = resolver.resolve_class(declaration, span);
+                resolver.current_class = enclosing_class;
+
+                result
+            }
+            StatementKind::Expression(inner) | StatementKind::Print(inner) => {
+                inner.resolve(resolver)
+            }
+            StatementKind::Function(declaration) => {
+                resolver.declare(declaration.name, span)?;
+                resolver.define(declaration.name);
+
+                resolver.resolve_function(declaration, FunctionKind::Function)
+            }
+            StatementKind::If(condition, then_branch, else_branch) => {
+                condition.resolve(resolver)?;
+                then_branch.resolve(resolver)?;
+                match else_branch {
+                    Some(else_branch) => else_branch.resolve(resolver),
+                    None => Ok(()),
+                }
+            }
+            StatementKind::Return(value) => {
+                match (resolver.current_function, value) {
+                    (FunctionKind::None, _) => Err(ResolverError::ReturnOutsideFunction(span)),
+                    (FunctionKind::Initializer, Some(_)) => {
+                        Err(ResolverError::ReturnValueFromInitializer(span))
+                    }
+                    (_, Some(value)) => value.resolve(resolver),
+                    (_, None) => Ok(()),
+                }
+            }
+            StatementKind::VariableDeclaration(identifier, initial_value) => {
+                resolver.declare(*identifier, span)?;
+                if let Some(initial_value) = initial_value {
+                    initial_value.resolve(resolver)?;
+                }
+                resolver.define(*identifier);
+
+                Ok(())
+            }
+            StatementKind::While(condition, body) => {
+                condition.resolve(resolver)?;
+                body.resolve(resolver)
+            }
+            StatementKind::Error => Ok(()),
+        }
+    }
+}
+
+impl Resolve for Expression {
+    fn resolve(&self, resolver: &mut Resolver) -> Result<(), ResolverError> {
+        match self {
+            Self::Literal(_) => Ok(()),
+            Self::Binary(inner) => {
+                let BinaryExpression { left, right, .. } = inner.as_ref();
+                left.resolve(resolver)?;
+                right.resolve(resolver)
+            }
+            Self::Logical(inner) => {
+                let LogicalExpression { left, right, .. } = inner.as_ref();
+                left.resolve(resolver)?;
+                right.resolve(resolver)
+            }
+            Self::Conditional(inner) => {
+                let ConditionalExpression {
+                    condition,
+                    then_branch,
+                    else_branch,
+                    ..
+                } = inner.as_ref();
+                condition.resolve(resolver)?;
+                then_branch.resolve(resolver)?;
+                else_branch.resolve(resolver)
+            }
+            Self::Call(inner) => {
+                let CallExpression {
+                    callee, arguments, ..
+                } = inner.as_ref();
+                callee.resolve(resolver)?;
+                arguments
+                    .iter()
+                    .try_for_each(|argument| argument.resolve(resolver))
+            }
+            Self::Get(inner) => {
+                let GetExpression { object, .. } = inner.as_ref();
+                object.resolve(resolver)
+            }
+            Self::Set(inner) => {
+                let SetExpression { object, value, .. } = inner.as_ref();
+                value.resolve(resolver)?;
+                object.resolve(resolver)
+            }
+            Self::Grouping(inner, _) => inner.resolve(resolver),
+            Self::Unary(inner) => {
+                let UnaryExpression { expression, .. } = inner.as_ref();
+                expression.resolve(resolver)
+            }
+            Self::VariableReference(identifier, depth, span) => {
+                let is_being_initialized = resolver
+                    .scopes
+                    .last()
+                    .and_then(|scope| scope.get(identifier))
+                    == Some(&false);
+
+                if is_being_initialized {
+                    return Err(ResolverError::ReadLocalInOwnInitializer(*identifier, *span));
+                }
+
+                resolver.resolve_local(*identifier, depth);
+                Ok(())
+            }
+            Self::Assign(inner) => {
+                let AssignExpression {
+                    identifier,
+                    value,
+                    depth,
+                    ..
+                } = inner.as_ref();
+                value.resolve(resolver)?;
+                resolver.resolve_local(*identifier, depth);
+                Ok(())
+            }
+            Self::This(depth, span) => {
+                if resolver.current_class == ClassKind::None {
+                    return Err(ResolverError::ThisOutsideClass(*span));
+                }
+
+                resolver.resolve_local(Symbol::THIS, depth);
+                Ok(())
+            }
+            Self::Super(SuperExpression { depth, span, .. }) => {
+                match resolver.current_class {
+                    ClassKind::None => return Err(ResolverError::SuperOutsideClass(*span)),
+                    ClassKind::Class => return Err(ResolverError::SuperWithoutSuperclass(*span)),
+                    ClassKind::Subclass => {}
+                }
+
+                resolver.resolve_local(Symbol::SUPER, depth);
+                Ok(())
+            }
+            Self::Interpolation(InterpolationExpression { parts, .. }) => {
+                parts.iter().try_for_each(|part| part.resolve(resolver))
+            }
+            Self::Error(_) => Ok(()),
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ResolverError {
+    #[error("Can't read local variable '{0}' in its own initializer.")]
+    ReadLocalInOwnInitializer(Symbol, Span),
+    #[error("Already a variable named '{0}' in this scope.")]
+    AlreadyDeclared(Symbol, Span),
+    #[error("Can't return from top-level code.")]
+    ReturnOutsideFunction(Span),
+    #[error("Can't return a value from an initializer.")]
+    ReturnValueFromInitializer(Span),
+    #[error("Can't use 'this' outside of a class.")]
+    ThisOutsideClass(Span),
+    #[error("Can't use 'super' outside of a class.")]
+    SuperOutsideClass(Span),
+    #[error("Can't use 'super' in a class with no superclass.")]
+    SuperWithoutSuperclass(Span),
+}
+
+impl ResolverError {
+    pub fn span(&self) -> Span {
+        match *self {
+            Self::ReadLocalInOwnInitializer(_, span)
+            | Self::AlreadyDeclared(_, span)
+            | Self::ReturnOutsideFunction(span)
+            | Self::ReturnValueFromInitializer(span)
+            | Self::ThisOutsideClass(span)
+            | Self::SuperOutsideClass(span)
+            | Self::SuperWithoutSuperclass(span) => span,
+        }
+    }
+
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        let diagnostic = Diagnostic::error("Resolver", self.to_string(), self.span());
+
+        match self {
+            Self::ReadLocalInOwnInitializer(identifier, _) => {
+                diagnostic.with_note(format!(
+                "'{identifier}' is declared by this same statement, it isn't ready to be read yet"
+            ))
+            }
+            Self::ReturnValueFromInitializer(_) => {
+                diagnostic.with_note("initializers always return the instance, 'this'")
+            }
+            Self::SuperWithoutSuperclass(_) => {
+                diagnostic.with_help("inherit from another class with 'class Name < Superclass'")
+            }
+            _ => diagnostic,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{lexer::Scanner, parser::Parser};
+
+    fn try_resolve(source_code: &str) -> Result<Vec<Statement>, ResolverError> {
+        let tokens = Scanner::new(source_code).try_scan_all().unwrap();
+        let statements = Parser::new(&tokens).try_parse().unwrap();
+
+        resolve_program(&statements).map(|()| statements)
+    }
+
+    #[test]
+    fn test_resolving_scope_depths() {
+        let statements = try_resolve("var a; { var b; { a; b; } }").unwrap();
+
+        let StatementKind::Block(outer) = &statements[1].kind else {
+            panic!()
+        };
+        let StatementKind::Block(inner) = &outer[1].kind else {
+            panic!()
+        };
+        let depths: Vec<_> = inner
+            .iter()
+            .map(|statement| {
+                match &statement.kind {
+                    StatementKind::Expression(Expression::VariableReference(_, depth, _)) => {
+                        depth.get()
+                    }
+                    _ => panic!(),
+                }
+            })
+            .collect();
+
+        // Globals stay unresolved.
+        assert_eq!(depths, [None, Some(1)]);
+    }
+
+    #[test]
+    fn test_static_errors() {
+        let read_in_initializer = try_resolve("{ var a = a; }").unwrap_err();
+        assert!(matches!(
+            read_in_initializer,
+            ResolverError::ReadLocalInOwnInitializer(..)
+        ));
+
+        let redeclaration = try_resolve("fun f(a) { var a; }").unwrap_err();
+        assert!(matches!(redeclaration, ResolverError::AlreadyDeclared(..)));
+
+        let duplicate_parameter = try_resolve("fun f(a,\n b,\n a) {}").unwrap_err();
+        assert!(matches!(
+            duplicate_parameter,
+            ResolverError::AlreadyDeclared(..)
+        ));
+        assert_eq!(duplicate_parameter.span().start.to_string(), "3:2");
+
+        let top_level_return = try_resolve("return 1;").unwrap_err();
+        assert!(matches!(
+            top_level_return,
+            ResolverError::ReturnOutsideFunction(_)
+        ));
+
+        let this_outside_class = try_resolve("fun f() { return this; }").unwrap_err();
+        assert!(matches!(
+            this_outside_class,
+            ResolverError::ThisOutsideClass(_)
+        ));
+    }
+
+    #[test]
+    fn test_globals_can_be_redeclared() {
+        assert!(try_resolve("var a = 1; var a = a;").is_ok());
+    }
+}
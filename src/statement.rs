@@ -1,8 +1,77 @@
-use crate::Expression;
+use std::rc::Rc;
+
+use crate::{expression::Expression, symbol::Symbol, token::Span};
+
+#[derive(Debug)]
+pub struct Statement {
+    pub kind: StatementKind,
+    /// Region of the source code this statement was parsed from.
+    pub span: Span,
+}
+
+impl Statement {
+    pub fn new(kind: StatementKind, span: Span) -> Self {
+        Self { kind, span }
+    }
+}
 
 #[derive(Debug)]
-pub enum Statement {
+pub enum StatementKind {
+    Block(Vec<Statement>),
+    Class(ClassDeclaration),
     Expression(Expression),
+    Function(Rc<FunctionDeclaration>),
+    If(Expression, Box<Statement>, Option<Box<Statement>>),
     Print(Expression),
-    VariableDeclaration(String, Option<Expression>),
+    Return(Option<Expression>),
+    VariableDeclaration(Symbol, Option<Expression>),
+    While(Expression, Box<Statement>),
+    /// Placeholder for a statement that failed to parse, see `Parser::parse_with_errors`.
+    Error,
+}
+
+#[derive(Debug)]
+pub struct FunctionDeclaration {
+    pub name: Symbol,
+    /// Names of the parameters, and where each one is.
+    pub parameters: Vec<(Symbol, Span)>,
+    pub body: Vec<Statement>,
+    pub span: Span,
+}
+
+impl FunctionDeclaration {
+    pub fn new(
+        name: Symbol,
+        parameters: Vec<(Symbol, Span)>,
+        body: Vec<Statement>,
+        span: Span,
+    ) -> Self {
+        Self {
+            name,
+            parameters,
+            body,
+            span,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ClassDeclaration {
+    pub name: Symbol,
+    pub superclass: Option<Expression>,
+    pub methods: Vec<Rc<FunctionDeclaration>>,
+}
+
+impl ClassDeclaration {
+    pub fn new(
+        name: Symbol,
+        superclass: Option<Expression>,
+        methods: Vec<Rc<FunctionDeclaration>>,
+    ) -> Self {
+        Self {
+            name,
+            superclass,
+            methods,
+        }
+    }
 }
@@ -0,0 +1,93 @@
+//! Interned strings.
+//!
+//! Identifiers and string literals are stored once in a shared table, and referred to by a
+//! `Symbol`, which is cheap to copy, hash and compare.
+
+use std::{collections::HashMap, fmt, sync::Mutex};
+
+use once_cell::sync::Lazy;
+
+/// Names the interpreter refers to, interned in this order when the table is created.
+const PREDEFINED: [&str; 3] = ["init", "super", "this"];
+
+static INTERNER: Lazy<Mutex<Interner>> = Lazy::new(|| {
+    let mut interner = Interner::default();
+    for name in PREDEFINED {
+        interner.intern(name);
+    }
+    Mutex::new(interner)
+});
+
+/// Id of an interned string.
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Symbol(u32);
+
+impl Symbol {
+    pub const INIT: Symbol = Symbol(0);
+    pub const SUPER: Symbol = Symbol(1);
+    pub const THIS: Symbol = Symbol(2);
+
+    /// Get the symbol of this string, adding it to the table if it's new.
+    pub fn intern(string: &str) -> Self {
+        INTERNER.lock().unwrap().intern(string)
+    }
+
+    pub fn as_str(self) -> &'static str {
+        INTERNER.lock().unwrap().strings[self.0 as usize]
+    }
+}
+
+impl From<&str> for Symbol {
+    fn from(string: &str) -> Self {
+        Self::intern(string)
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
+/// Shown like the string it stands for.
+impl fmt::Debug for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+/// Interned strings are never freed, they're leaked so symbols can hand out `&'static str`s.
+#[derive(Default)]
+struct Interner {
+    symbols: HashMap<&'static str, Symbol>,
+    strings: Vec<&'static str>,
+}
+
+impl Interner {
+    fn intern(&mut self, string: &str) -> Symbol {
+        if let Some(&symbol) = self.symbols.get(string) {
+            return symbol;
+        }
+
+        let string: &'static str = Box::leak(string.into());
+        let symbol = Symbol(self.strings.len() as u32);
+        self.strings.push(string);
+        self.symbols.insert(string, symbol);
+        symbol
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interning_the_same_string_twice() {
+        let symbol = Symbol::intern("interned");
+
+        assert_eq!(Symbol::intern("interned"), symbol);
+        assert_ne!(Symbol::intern("other"), symbol);
+        assert_eq!(symbol.as_str(), "interned");
+        assert_eq!(Symbol::intern("this"), Symbol::THIS);
+    }
+}
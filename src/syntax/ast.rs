@@ -0,0 +1,456 @@
+//! Typed views over the CST.
+//!
+//! Each view wraps a `SyntaxNode` of one `SyntaxKind`, with accessors for its parts. Parts that
+//! are missing because of syntax errors are `None`.
+
+use std::ops::Not;
+
+use super::{SyntaxElement, SyntaxKind, SyntaxNode};
+use crate::{symbol::Symbol, token::Token};
+
+/// A typed view over a `SyntaxNode`.
+pub trait AstNode<'t, 'a>: Sized {
+    fn cast(node: &'t SyntaxNode<'a>) -> Option<Self>;
+    fn syntax(&self) -> &'t SyntaxNode<'a>;
+}
+
+macro_rules! ast_node {
+    ($name:ident) => {
+        #[derive(Debug, Clone, Copy)]
+        pub struct $name<'t, 'a>(&'t SyntaxNode<'a>);
+
+        impl<'t, 'a> AstNode<'t, 'a> for $name<'t, 'a> {
+            fn cast(node: &'t SyntaxNode<'a>) -> Option<Self> {
+                (node.kind == SyntaxKind::$name).then_some(Self(node))
+            }
+
+            fn syntax(&self) -> &'t SyntaxNode<'a> {
+                self.0
+            }
+        }
+    };
+}
+
+ast_node!(Program);
+ast_node!(ClassDeclaration);
+ast_node!(Function);
+ast_node!(VariableDeclaration);
+ast_node!(Block);
+ast_node!(ExpressionStatement);
+ast_node!(For);
+ast_node!(If);
+ast_node!(Print);
+ast_node!(Return);
+ast_node!(While);
+ast_node!(Assign);
+ast_node!(Binary);
+ast_node!(Call);
+ast_node!(Get);
+ast_node!(Grouping);
+ast_node!(Interpolation);
+ast_node!(Literal);
+ast_node!(Logical);
+ast_node!(Conditional);
+ast_node!(Set);
+ast_node!(Super);
+ast_node!(This);
+ast_node!(Unary);
+ast_node!(VariableReference);
+
+impl<'t, 'a> Program<'t, 'a> {
+    /// Declarations and statements at the top level, error nodes included.
+    pub fn statements(&self) -> impl Iterator<Item = &'t SyntaxNode<'a>> {
+        self.0.child_nodes()
+    }
+
+    pub fn functions(&self) -> impl Iterator<Item = Function<'t, 'a>> {
+        self.0.child_nodes().filter_map(Function::cast)
+    }
+
+    pub fn classes(&self) -> impl Iterator<Item = ClassDeclaration<'t, 'a>> {
+        self.0.child_nodes().filter_map(ClassDeclaration::cast)
+    }
+}
+
+impl<'t, 'a> ClassDeclaration<'t, 'a> {
+    pub fn name(&self) -> Option<Symbol> {
+        identifiers(self.0).next()
+    }
+
+    pub fn superclass(&self) -> Option<Symbol> {
+        let superclass = self
+            .0
+            .child_nodes()
+            .find(|node| node.kind == SyntaxKind::VariableReference)?;
+        identifiers(superclass).next()
+    }
+
+    pub fn methods(&self) -> impl Iterator<Item = Function<'t, 'a>> {
+        self.0.child_nodes().filter_map(Function::cast)
+    }
+}
+
+impl<'t, 'a> Function<'t, 'a> {
+    pub fn name(&self) -> Option<Symbol> {
+        identifiers(self.0).next()
+    }
+
+    pub fn parameters(&self) -> impl Iterator<Item = Symbol> + 't {
+        identifiers(self.0).skip(1)
+    }
+
+    pub fn body(&self) -> Option<Block<'t, 'a>> {
+        self.0.child_nodes().find_map(Block::cast)
+    }
+}
+
+impl<'t, 'a> VariableDeclaration<'t, 'a> {
+    pub fn name(&self) -> Option<Symbol> {
+        identifiers(self.0).next()
+    }
+
+    pub fn initializer(&self) -> Option<&'t SyntaxNode<'a>> {
+        self.0.child_nodes().next()
+    }
+}
+
+impl<'t, 'a> Block<'t, 'a> {
+    pub fn statements(&self) -> impl Iterator<Item = &'t SyntaxNode<'a>> {
+        self.0.child_nodes()
+    }
+}
+
+impl<'t, 'a> ExpressionStatement<'t, 'a> {
+    pub fn expression(&self) -> Option<&'t SyntaxNode<'a>> {
+        self.0.child_nodes().next()
+    }
+}
+
+/// The parts of a `for` are all optional, so they're told apart by the `(`, `;` and `)` around
+/// them.
+impl<'t, 'a> For<'t, 'a> {
+    /// A `VariableDeclaration` or an `ExpressionStatement`.
+    pub fn initializer(&self) -> Option<&'t SyntaxNode<'a>> {
+        node_after(self.0, Token::LeftParen)
+    }
+
+    pub fn condition(&self) -> Option<&'t SyntaxNode<'a>> {
+        node_before(self.0, Token::Semicolon).filter(|node| is_statement(node).not())
+    }
+
+    pub fn increment(&self) -> Option<&'t SyntaxNode<'a>> {
+        node_before(self.0, Token::RightParen)
+    }
+
+    pub fn body(&self) -> Option<&'t SyntaxNode<'a>> {
+        node_after(self.0, Token::RightParen)
+    }
+}
+
+impl<'t, 'a> If<'t, 'a> {
+    pub fn condition(&self) -> Option<&'t SyntaxNode<'a>> {
+        self.0.child_nodes().next()
+    }
+
+    pub fn then_branch(&self) -> Option<&'t SyntaxNode<'a>> {
+        self.0.child_nodes().nth(1)
+    }
+
+    pub fn else_branch(&self) -> Option<&'t SyntaxNode<'a>> {
+        self.0.child_nodes().nth(2)
+    }
+}
+
+impl<'t, 'a> Print<'t, 'a> {
+    pub fn expression(&self) -> Option<&'t SyntaxNode<'a>> {
+        self.0.child_nodes().next()
+    }
+}
+
+impl<'t, 'a> Return<'t, 'a> {
+    pub fn value(&self) -> Option<&'t SyntaxNode<'a>> {
+        self.0.child_nodes().next()
+    }
+}
+
+impl<'t, 'a> While<'t, 'a> {
+    pub fn condition(&self) -> Option<&'t SyntaxNode<'a>> {
+        self.0.child_nodes().next()
+    }
+
+    pub fn body(&self) -> Option<&'t SyntaxNode<'a>> {
+        self.0.child_nodes().nth(1)
+    }
+}
+
+impl<'t, 'a> Assign<'t, 'a> {
+    pub fn name(&self) -> Option<Symbol> {
+        identifiers(self.0.child_nodes().next()?).next()
+    }
+
+    pub fn value(&self) -> Option<&'t SyntaxNode<'a>> {
+        self.0.child_nodes().nth(1)
+    }
+}
+
+impl<'t, 'a> Binary<'t, 'a> {
+    pub fn left(&self) -> Option<&'t SyntaxNode<'a>> {
+        self.0.child_nodes().next()
+    }
+
+    pub fn operator(&self) -> Option<Token> {
+        first_token(self.0)
+    }
+
+    pub fn right(&self) -> Option<&'t SyntaxNode<'a>> {
+        self.0.child_nodes().nth(1)
+    }
+}
+
+impl<'t, 'a> Call<'t, 'a> {
+    pub fn callee(&self) -> Option<&'t SyntaxNode<'a>> {
+        self.0.child_nodes().next()
+    }
+
+    pub fn arguments(&self) -> impl Iterator<Item = &'t SyntaxNode<'a>> {
+        self.0.child_nodes().skip(1)
+    }
+}
+
+impl<'t, 'a> Get<'t, 'a> {
+    pub fn object(&self) -> Option<&'t SyntaxNode<'a>> {
+        self.0.child_nodes().next()
+    }
+
+    pub fn name(&self) -> Option<Symbol> {
+        identifiers(self.0).next()
+    }
+}
+
+impl<'t, 'a> Grouping<'t, 'a> {
+    pub fn expression(&self) -> Option<&'t SyntaxNode<'a>> {
+        self.0.child_nodes().next()
+    }
+}
+
+impl<'t, 'a> Interpolation<'t, 'a> {
+    /// Text of the string around the interpolated expressions, there's always one more segment
+    /// than expressions.
+    pub fn segments(&self) -> impl Iterator<Item = Symbol> + 't {
+        self.0.child_tokens().filter_map(|token| {
+            match token.token {
+                Some(
+                    Token::Interpolation(segment)
+                    | Token::InterpolationMiddle(segment)
+                    | Token::InterpolationEnd(segment),
+                ) => Some(segment),
+                _ => None,
+            }
+        })
+    }
+
+    pub fn expressions(&self) -> impl Iterator<Item = &'t SyntaxNode<'a>> {
+        self.0.child_nodes()
+    }
+}
+
+impl<'t, 'a> Literal<'t, 'a> {
+    pub fn value(&self) -> Option<Token> {
+        first_token(self.0)
+    }
+}
+
+impl<'t, 'a> Logical<'t, 'a> {
+    pub fn left(&self) -> Option<&'t SyntaxNode<'a>> {
+        self.0.child_nodes().next()
+    }
+
+    pub fn operator(&self) -> Option<Token> {
+        first_token(self.0)
+    }
+
+    pub fn right(&self) -> Option<&'t SyntaxNode<'a>> {
+        self.0.child_nodes().nth(1)
+    }
+}
+
+impl<'t, 'a> Conditional<'t, 'a> {
+    pub fn condition(&self) -> Option<&'t SyntaxNode<'a>> {
+        self.0.child_nodes().next()
+    }
+
+    pub fn then_branch(&self) -> Option<&'t SyntaxNode<'a>> {
+        self.0.child_nodes().nth(1)
+    }
+
+    pub fn else_branch(&self) -> Option<&'t SyntaxNode<'a>> {
+        self.0.child_nodes().nth(2)
+    }
+}
+
+/// The target of a `Set` is parsed first as a `Get`, which is kept as its first child.
+impl<'t, 'a> Set<'t, 'a> {
+    pub fn object(&self) -> Option<&'t SyntaxNode<'a>> {
+        self.target()?.object()
+    }
+
+    pub fn name(&self) -> Option<Symbol> {
+        self.target()?.name()
+    }
+
+    pub fn value(&self) -> Option<&'t SyntaxNode<'a>> {
+        self.0.child_nodes().nth(1)
+    }
+
+    fn target(&self) -> Option<Get<'t, 'a>> {
+        self.0.child_nodes().next().and_then(Get::cast)
+    }
+}
+
+impl<'t, 'a> Super<'t, 'a> {
+    pub fn method(&self) -> Option<Symbol> {
+        identifiers(self.0).next()
+    }
+}
+
+impl<'t, 'a> Unary<'t, 'a> {
+    pub fn operator(&self) -> Option<Token> {
+        first_token(self.0)
+    }
+
+    pub fn operand(&self) -> Option<&'t SyntaxNode<'a>> {
+        self.0.child_nodes().next()
+    }
+}
+
+impl<'t, 'a> VariableReference<'t, 'a> {
+    pub fn name(&self) -> Option<Symbol> {
+        identifiers(self.0).next()
+    }
+}
+
+/// First token that is a direct child of `node`, like the operator of a `Binary`.
+fn first_token(node: &SyntaxNode) -> Option<Token> {
+    node.child_tokens()
+        .find(|token| token.is_trivia().not())
+        .and_then(|token| token.token)
+}
+
+fn is_statement(node: &SyntaxNode) -> bool {
+    matches!(
+        node.kind,
+        SyntaxKind::VariableDeclaration | SyntaxKind::ExpressionStatement
+    )
+}
+
+/// Child node right before the last `token` that is a direct child of `node`.
+fn node_before<'t, 'a>(node: &'t SyntaxNode<'a>, token: Token) -> Option<&'t SyntaxNode<'a>> {
+    let index = node
+        .children
+        .iter()
+        .rposition(|child| is_token(child, token))?;
+    as_node(node.children[..index].iter().rev())
+}
+
+/// Child node right after the first `token` that is a direct child of `node`.
+fn node_after<'t, 'a>(node: &'t SyntaxNode<'a>, token: Token) -> Option<&'t SyntaxNode<'a>> {
+    let index = node
+        .children
+        .iter()
+        .position(|child| is_token(child, token))?;
+    as_node(node.children[index + 1..].iter())
+}
+
+fn is_token(element: &SyntaxElement, expected: Token) -> bool {
+    matches!(element, SyntaxElement::Token(token) if token.token == Some(expected))
+}
+
+/// The first element that isn't trivia, if it's a node.
+fn as_node<'t, 'a>(
+    mut elements: impl Iterator<Item = &'t SyntaxElement<'a>>,
+) -> Option<&'t SyntaxNode<'a>> {
+    let element = elements.find(|element| {
+        match element {
+            SyntaxElement::Node(_) => true,
+            SyntaxElement::Token(token) => token.is_trivia().not(),
+        }
+    })?;
+    match element {
+        SyntaxElement::Node(node) => Some(node),
+        SyntaxElement::Token(_) => None,
+    }
+}
+
+/// Identifiers that are direct children of `node`.
+fn identifiers<'t>(node: &'t SyntaxNode) -> impl Iterator<Item = Symbol> + 't {
+    node.child_tokens().filter_map(|token| {
+        match token.token {
+            Some(Token::Identifier(identifier)) => Some(identifier),
+            _ => None,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::syntax::parse;
+
+    #[test]
+    fn test_typed_views() {
+        let source_code = "\
+            var x = 1 + 2;\n\
+            // Greets someone.\n\
+            fun greet(name, greeting) { print greeting + name; }\n\
+            class B < A { init() {} }\n\
+            for (; i < 3;) if (a) print f(1, x ? 2 : 3); else a.b = \"${c}!\";";
+
+        let parse = parse(source_code);
+        let program = Program::cast(&parse.tree).unwrap();
+
+        let variable = VariableDeclaration::cast(program.statements().next().unwrap()).unwrap();
+        assert_eq!(variable.name(), Some("x".into()));
+        let initializer = Binary::cast(variable.initializer().unwrap()).unwrap();
+        assert_eq!(initializer.operator(), Some(Token::Plus));
+        assert_eq!(initializer.right().unwrap().to_string(), "2");
+
+        let function = program.functions().next().unwrap();
+        let comments = parse.tree.comments_before(function.syntax());
+        assert_eq!(comments[0].text, "// Greets someone.");
+        assert_eq!(function.name(), Some("greet".into()));
+        let parameters: Vec<_> = function.parameters().map(Symbol::as_str).collect();
+        assert_eq!(parameters, ["name", "greeting"]);
+        assert_eq!(function.body().unwrap().statements().count(), 1);
+
+        let class = program.classes().next().unwrap();
+        assert_eq!(class.name(), Some("B".into()));
+        assert_eq!(class.superclass(), Some("A".into()));
+        assert_eq!(class.methods().next().unwrap().name(), Some(Symbol::INIT));
+
+        let for_loop = For::cast(program.statements().nth(3).unwrap()).unwrap();
+        assert!(for_loop.initializer().is_none());
+        assert_eq!(for_loop.condition().unwrap().to_string(), "i < 3");
+        assert!(for_loop.increment().is_none());
+
+        let if_statement = If::cast(for_loop.body().unwrap()).unwrap();
+        let condition = VariableReference::cast(if_statement.condition().unwrap()).unwrap();
+        assert_eq!(condition.name(), Some("a".into()));
+
+        let print = Print::cast(if_statement.then_branch().unwrap()).unwrap();
+        let call = Call::cast(print.expression().unwrap()).unwrap();
+        assert_eq!(call.callee().unwrap().to_string(), "f");
+        let arguments: Vec<_> = call.arguments().collect();
+        assert_eq!(arguments.len(), 2);
+        let conditional = Conditional::cast(arguments[1]).unwrap();
+        assert_eq!(conditional.else_branch().unwrap().to_string(), "3");
+
+        let statement = ExpressionStatement::cast(if_statement.else_branch().unwrap()).unwrap();
+        let set = Set::cast(statement.expression().unwrap()).unwrap();
+        assert_eq!(set.object().unwrap().to_string(), "a");
+        assert_eq!(set.name(), Some("b".into()));
+        let interpolation = Interpolation::cast(set.value().unwrap()).unwrap();
+        let segments: Vec<_> = interpolation.segments().map(Symbol::as_str).collect();
+        assert_eq!(segments, ["", "!"]);
+        assert_eq!(interpolation.expressions().count(), 1);
+    }
+}
@@ -0,0 +1,436 @@
+//! Lossless concrete syntax tree (CST), for tools that need to reproduce the source code, like
+//! formatters and refactoring tools.
+//!
+//! Unlike the AST, the CST keeps every token, including whitespace, comments and text the
+//! scanner couldn't make sense of, so printing it gives back the exact source code. It's built
+//! from the same parser as the AST, which records where each node starts and ends while parsing.
+//!
+//! Typed views over the CST live in `ast`.
+
+pub mod ast;
+
+use std::{fmt, mem, ops::Not};
+
+use crate::{
+    error::{LexerErrorReporter, ParserErrorReporter},
+    expression::Expression,
+    lexer::Scanner,
+    parser::Parser,
+    statement::StatementKind,
+    token::{Position, Span, Token, TokenWithPosition},
+};
+
+/// Result of parsing a program into a CST, errors don't stop the parsing.
+#[derive(Debug)]
+pub struct Parse<'a> {
+    pub tree: SyntaxNode<'a>,
+    pub lexer_errors: LexerErrorReporter,
+    pub parser_errors: ParserErrorReporter,
+}
+
+/// Parse a program into a CST that covers all of `source_code`, even if it has errors.
+pub fn parse(source_code: &str) -> Parse<'_> {
+    let mut lossless_tokens = vec![];
+    let mut lexer_errors = LexerErrorReporter::new();
+
+    // Text of tokens that failed to scan is added to the tree later, from the gaps left by them.
+    for result in Scanner::new_lossless(source_code) {
+        match result {
+            Ok(token) => lossless_tokens.push(token),
+            Err(error) => lexer_errors.add_lexer_error(error),
+        }
+    }
+
+    let tokens: Vec<_> = lossless_tokens
+        .iter()
+        .filter(|token| token.token_type.is_trivia().not())
+        .cloned()
+        .collect();
+    let (events, parser_errors) = Parser::new_lossless(&tokens).parse_syntax_events();
+
+    let mut builder = TreeBuilder::new(source_code, lossless_tokens);
+    for event in events {
+        match event {
+            SyntaxEvent::Start(kind) => builder.start_node(kind),
+            SyntaxEvent::Token => builder.add_token(),
+            SyntaxEvent::Finish => builder.finish_node(),
+        }
+    }
+
+    Parse {
+        tree: builder.finish(),
+        lexer_errors,
+        parser_errors,
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SyntaxKind {
+    Program,
+
+    // Declarations and statements
+    ClassDeclaration,
+    /// Function declaration or method, with its body.
+    Function,
+    VariableDeclaration,
+    Block,
+    ExpressionStatement,
+    For,
+    If,
+    Print,
+    Return,
+    While,
+
+    // Expressions
+    Assign,
+    Binary,
+    Call,
+    Get,
+    Grouping,
+    Interpolation,
+    Literal,
+    Logical,
+    Conditional,
+    Set,
+    Super,
+    This,
+    Unary,
+    VariableReference,
+
+    /// Tokens skipped by the parser while recovering from an error.
+    Error,
+}
+
+impl SyntaxKind {
+    /// `for` loops are desugared in the AST, so they can't be told apart from blocks here.
+    pub fn of_statement(kind: &StatementKind) -> Self {
+        match kind {
+            StatementKind::Block(_) => Self::Block,
+            StatementKind::Class(_) => Self::ClassDeclaration,
+            StatementKind::Expression(_) => Self::ExpressionStatement,
+            StatementKind::Function(_) => Self::Function,
+            StatementKind::If(..) => Self::If,
+            StatementKind::Print(_) => Self::Print,
+            StatementKind::Return(_) => Self::Return,
+            StatementKind::VariableDeclaration(..) => Self::VariableDeclaration,
+            StatementKind::While(..) => Self::While,
+            StatementKind::Error => Self::Error,
+        }
+    }
+
+    pub fn of_expression(expression: &Expression) -> Self {
+        match expression {
+            Expression::Literal(_) => Self::Literal,
+            Expression::Binary(_) => Self::Binary,
+            Expression::Call(_) => Self::Call,
+            Expression::Get(_) => Self::Get,
+            Expression::Set(_) => Self::Set,
+            Expression::Logical(_) => Self::Logical,
+            Expression::Conditional(_) => Self::Conditional,
+            Expression::Grouping(..) => Self::Grouping,
+            Expression::Unary(_) => Self::Unary,
+            Expression::VariableReference(..) => Self::VariableReference,
+            Expression::Assign(_) => Self::Assign,
+            Expression::This(..) => Self::This,
+            Expression::Super(_) => Self::Super,
+            Expression::Interpolation(_) => Self::Interpolation,
+            Expression::Error(_) => Self::Error,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyntaxNode<'a> {
+    pub kind: SyntaxKind,
+    pub children: Vec<SyntaxElement<'a>>,
+}
+
+impl<'a> SyntaxNode<'a> {
+    pub fn child_nodes(&self) -> impl Iterator<Item = &SyntaxNode<'a>> {
+        self.children.iter().filter_map(|child| {
+            match child {
+                SyntaxElement::Node(node) => Some(node),
+                SyntaxElement::Token(_) => None,
+            }
+        })
+    }
+
+    /// Tokens that are direct children of this node.
+    pub fn child_tokens(&self) -> impl Iterator<Item = &SyntaxToken<'a>> {
+        self.children.iter().filter_map(|child| {
+            match child {
+                SyntaxElement::Node(_) => None,
+                SyntaxElement::Token(token) => Some(token),
+            }
+        })
+    }
+
+    /// Comments right before `child`, not separated from it by a blank line, like
+    /// documentation comments.
+    pub fn comments_before(&self, child: &SyntaxNode) -> Vec<&SyntaxToken<'a>> {
+        let is_child = |element: &SyntaxElement| matches!(element, SyntaxElement::Node(node) if std::ptr::eq(node, child));
+        let Some(index) = self.children.iter().position(is_child) else {
+            return vec![];
+        };
+
+        let mut comments = vec![];
+        for element in self.children[..index].iter().rev() {
+            let SyntaxElement::Token(token) = element else {
+                break;
+            };
+            match token.token {
+                Some(Token::Comment) => comments.push(token),
+                Some(Token::Whitespace) if token.text.matches('\n').count() < 2 => {}
+                _ => break,
+            }
+        }
+
+        comments.reverse();
+        comments
+    }
+
+    /// Region of the source code covered by this node, `None` if it's empty.
+    pub fn span(&self) -> Option<Span> {
+        let first = self.children.first()?.span()?;
+        let last = self.children.iter().rev().find_map(SyntaxElement::span)?;
+        Some(first.to(last))
+    }
+}
+
+/// Prints the exact source code this node was parsed from.
+impl fmt::Display for SyntaxNode<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.children.iter().try_for_each(|child| child.fmt(f))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SyntaxElement<'a> {
+    Node(SyntaxNode<'a>),
+    Token(SyntaxToken<'a>),
+}
+
+impl SyntaxElement<'_> {
+    fn span(&self) -> Option<Span> {
+        match self {
+            Self::Node(node) => node.span(),
+            Self::Token(token) => Some(token.span),
+        }
+    }
+}
+
+impl fmt::Display for SyntaxElement<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Node(node) => node.fmt(f),
+            Self::Token(token) => token.text.fmt(f),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyntaxToken<'a> {
+    /// `None` for text that the scanner failed to make a token of.
+    pub token: Option<Token>,
+    pub text: &'a str,
+    pub span: Span,
+}
+
+impl SyntaxToken<'_> {
+    pub fn is_trivia(&self) -> bool {
+        self.token.as_ref().is_none_or(Token::is_trivia)
+    }
+}
+
+/// What the parser did, in order, replayed by `TreeBuilder` to build the CST.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SyntaxEvent {
+    Start(SyntaxKind),
+    /// Consumed the next non-trivia token.
+    Token,
+    Finish,
+}
+
+/// Builds a CST out of `SyntaxEvent`s, adding the trivia the parser didn't see.
+struct TreeBuilder<'a> {
+    source_code: &'a str,
+    /// Every token scanned, trivia included, in order.
+    tokens: std::vec::IntoIter<TokenWithPosition>,
+    /// Where the text added to the tree so far ends.
+    end: Position,
+    /// Open nodes, innermost last, with their children so far.
+    parents: Vec<(SyntaxKind, Vec<SyntaxElement<'a>>)>,
+}
+
+impl<'a> TreeBuilder<'a> {
+    fn new(source_code: &'a str, tokens: Vec<TokenWithPosition>) -> Self {
+        Self {
+            source_code,
+            tokens: tokens.into_iter(),
+            end: Position::default(),
+            parents: vec![(SyntaxKind::Program, vec![])],
+        }
+    }
+
+    /// Trivia before a node goes to its parent, so it's added before starting the node.
+    fn start_node(&mut self, kind: SyntaxKind) {
+        self.add_trivia();
+        self.parents.push((kind, vec![]));
+    }
+
+    fn finish_node(&mut self) {
+        let (kind, children) = self.parents.pop().unwrap();
+        let node = SyntaxNode { kind, children };
+        self.push(SyntaxElement::Node(node));
+    }
+
+    /// Add the next non-trivia token, and the trivia before it.
+    fn add_token(&mut self) {
+        self.add_trivia();
+        if let Some(token) = self.tokens.next() {
+            self.push_token(token);
+        }
+    }
+
+    fn add_trivia(&mut self) {
+        while let Some(token) = self.tokens.as_slice().first() {
+            if token.token_type.is_trivia().not() {
+                break;
+            }
+            let token = self.tokens.next().unwrap();
+            self.push_token(token);
+        }
+    }
+
+    /// Add a token, and before it, the text of the tokens that failed to scan, if any.
+    fn push_token(&mut self, token: TokenWithPosition) {
+        self.push_gap_until(token.span.start);
+
+        let text = &self.source_code[token.span.byte_range()];
+        self.end = token.span.end;
+        self.push(SyntaxElement::Token(SyntaxToken {
+            token: Some(token.token_type),
+            text,
+            span: token.span,
+        }));
+    }
+
+    fn push_gap_until(&mut self, end: Position) {
+        if self.end.offset < end.offset {
+            let span = Span::new(self.end, end);
+            let text = &self.source_code[span.byte_range()];
+            self.end = end;
+            self.push(SyntaxElement::Token(SyntaxToken {
+                token: None,
+                text,
+                span,
+            }));
+        }
+    }
+
+    fn push(&mut self, element: SyntaxElement<'a>) {
+        // There's always the root node.
+        self.parents.last_mut().unwrap().1.push(element);
+    }
+
+    fn finish(mut self) -> SyntaxNode<'a> {
+        while self.tokens.len() > 0 {
+            self.add_token();
+        }
+
+        // The gap at the end is either a token that failed to scan, or nothing.
+        let mut end = self.end;
+        for ch in self.source_code[end.offset..].chars() {
+            end.advance(ch);
+        }
+        self.push_gap_until(end);
+
+        while self.parents.len() > 1 {
+            self.finish_node();
+        }
+        let (kind, children) = mem::take(&mut self.parents).pop().unwrap();
+        SyntaxNode { kind, children }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Kinds of the nodes, depth-first, indented by depth.
+    fn outline(node: &SyntaxNode, depth: usize, lines: &mut Vec<String>) {
+        lines.push(format!("{}{:?}", "  ".repeat(depth), node.kind));
+        for child in node.child_nodes() {
+            outline(child, depth + 1, lines);
+        }
+    }
+
+    #[test]
+    fn test_syntax_tree_round_trips_the_source() {
+        let source_code = "\
+            // Comment\n\
+            var a = 1 +   2 ; /* block /* nested */ */\n\
+            for (var i = 0; i < 3; i = i + 1) print \"${i}\";\n\
+            fun f(x) { return x.y(); }\n\
+            \t\n";
+
+        let parse = parse(source_code);
+        assert!(parse.lexer_errors.has_errors().not());
+        assert!(parse.parser_errors.has_errors().not());
+        assert_eq!(parse.tree.to_string(), source_code);
+
+        let mut lines = vec![];
+        outline(&parse.tree, 0, &mut lines);
+        assert_eq!(
+            lines,
+            [
+                "Program",
+                "  VariableDeclaration",
+                "    Binary",
+                "      Literal",
+                "      Literal",
+                "  For",
+                "    VariableDeclaration",
+                "      Literal",
+                "    Binary",
+                "      VariableReference",
+                "      Literal",
+                "    Assign",
+                "      VariableReference",
+                "      Binary",
+                "        VariableReference",
+                "        Literal",
+                "    Print",
+                "      Interpolation",
+                "        VariableReference",
+                "  Function",
+                "    Block",
+                "      Return",
+                "        Call",
+                "          Get",
+                "            VariableReference",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_syntax_tree_round_trips_invalid_source() {
+        let source_code = "var a = 1 + ; print @ \"ok\"; print (2;\n\"unterminated";
+
+        let parse = parse(source_code);
+        assert!(parse.lexer_errors.has_errors());
+        assert!(parse.parser_errors.has_errors());
+        assert_eq!(parse.tree.to_string(), source_code);
+    }
+
+    #[test]
+    fn test_stray_closing_delimiter_is_a_single_error_node() {
+        let source_code = "print ) ;";
+
+        let parse = parse(source_code);
+        let kinds: Vec<_> = parse.tree.child_nodes().map(|node| node.kind).collect();
+        assert_eq!(kinds, [SyntaxKind::Error]);
+        assert_eq!(parse.parser_errors.errors().len(), 1);
+        assert_eq!(parse.tree.to_string(), source_code);
+    }
+}
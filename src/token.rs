@@ -1,6 +1,8 @@
-use std::fmt;
+use std::{fmt, ops::Range};
 
-#[derive(Debug, Clone, PartialEq)]
+use crate::symbol::Symbol;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Token {
     // Single-character tokens
     LeftParen,
@@ -14,6 +16,8 @@ pub enum Token {
     Semicolon,
     Slash,
     Star,
+    Question,
+    Colon,
 
     // One Or Two Character tokens
     Bang,
@@ -26,8 +30,14 @@ pub enum Token {
     LessEqual,
 
     // Literals
-    Identifier(String),
-    String(String),
+    Identifier(Symbol),
+    String(Symbol),
+    /// Segment of a string that comes before an interpolated expression, like `"a ${`.
+    Interpolation(Symbol),
+    /// Segment between two interpolated expressions, like `} b ${`.
+    InterpolationMiddle(Symbol),
+    /// Segment after the last interpolated expression, like `} c"`.
+    InterpolationEnd(Symbol),
     Number(f64),
     Bool(bool),
     Nil,
@@ -46,6 +56,10 @@ pub enum Token {
     This,
     Var,
     While,
+
+    // Trivia, only kept by a lossless `Scanner`
+    Whitespace,
+    Comment,
 }
 
 impl Token {
@@ -56,6 +70,11 @@ impl Token {
         )
     }
 
+    /// Tokens that don't affect the meaning of the program.
+    pub fn is_trivia(&self) -> bool {
+        matches!(self, Token::Whitespace | Token::Comment)
+    }
+
     pub fn is_start_of_statement(&self) -> bool {
         matches!(
             self,
@@ -76,7 +95,11 @@ impl fmt::Display for Token {
         use Token::*;
 
         let txt = match self {
-            Identifier(inner) | String(inner) => return inner.fmt(f),
+            Identifier(inner)
+            | String(inner)
+            | Interpolation(inner)
+            | InterpolationMiddle(inner)
+            | InterpolationEnd(inner) => return inner.fmt(f),
             Number(inner) => return inner.fmt(f),
             Bool(inner) => return inner.fmt(f),
             LeftParen => "(",
@@ -90,6 +113,8 @@ impl fmt::Display for Token {
             Semicolon => ";",
             Slash => "/",
             Star => "*",
+            Question => "?",
+            Colon => ":",
             Bang => "!",
             BangEqual => "!=",
             Equal => "=",
@@ -112,6 +137,8 @@ impl fmt::Display for Token {
             This => "this",
             Var => "var",
             While => "while",
+            Whitespace => "whitespace",
+            Comment => "comment",
         };
 
         txt.fmt(f)
@@ -121,15 +148,12 @@ impl fmt::Display for Token {
 #[derive(Clone, Debug, PartialEq)]
 pub struct TokenWithPosition {
     pub token_type: Token,
-    pub position: Position,
+    pub span: Span,
 }
 
 impl TokenWithPosition {
-    pub fn new(token_type: Token, position: Position) -> Self {
-        Self {
-            token_type,
-            position,
-        }
+    pub fn new(token_type: Token, span: Span) -> Self {
+        Self { token_type, span }
     }
 }
 
@@ -139,37 +163,155 @@ impl fmt::Display for TokenWithPosition {
     }
 }
 
+/// Location in the source code.
+///
+/// `line` and `column` start at 1, and columns count `char`s. `offset` is the number of bytes
+/// before this location, so it can be used to slice the source.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct Position {
     pub line: usize,
     pub column: usize,
+    pub offset: usize,
 }
 
 impl Position {
     #[cfg(test)]
-    pub fn new(line: usize, column: usize) -> Self {
-        Self { line, column }
-    }
-
-    pub fn advance_line(&mut self) {
-        self.line += 1;
-        self.column = 1;
+    pub fn new(line: usize, column: usize, offset: usize) -> Self {
+        Self {
+            line,
+            column,
+            offset,
+        }
     }
 
-    pub fn advance_column(&mut self) {
-        self.column += 1;
+    /// Move past the given character.
+    pub fn advance(&mut self, ch: char) {
+        if ch == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        self.offset += ch.len_utf8();
     }
 }
 
 impl Default for Position {
     fn default() -> Self {
-        Position { line: 1, column: 1 }
+        Position {
+            line: 1,
+            column: 1,
+            offset: 0,
+        }
     }
 }
 
 impl fmt::Display for Position {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let Self { line, column } = self;
+        let Self { line, column, .. } = self;
         write!(f, "{line}:{column}")
     }
 }
+
+/// Region of the source code, from `start` up to (but not including) `end`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Span {
+    pub start: Position,
+    pub end: Position,
+}
+
+impl Span {
+    pub fn new(start: Position, end: Position) -> Self {
+        Self { start, end }
+    }
+
+    /// Span that starts at this one and ends where `other` ends.
+    pub fn to(self, other: Span) -> Span {
+        Span::new(self.start, other.end)
+    }
+
+    /// Byte range of the source code covered by this span.
+    pub fn byte_range(self) -> Range<usize> {
+        self.start.offset..self.end.offset
+    }
+
+    /// Line and column of both ends of this span, with columns counted in code units of the
+    /// given encoding.
+    ///
+    /// `source` must be the code this span was scanned from.
+    pub fn line_columns(self, source: &str, encoding: Encoding) -> (LineColumn, LineColumn) {
+        let start = LineColumn::from_offset(source, self.start.offset, encoding);
+        let end = LineColumn::from_offset(source, self.end.offset, encoding);
+        (start, end)
+    }
+}
+
+/// Unit used to count columns, LSP clients usually expect UTF-16.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Encoding {
+    Utf8,
+    Utf16,
+}
+
+/// Location in the source code with columns counted in code units of some `Encoding`.
+///
+/// Unlike `Position`, both start at 0, like the positions of LSP.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct LineColumn {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl LineColumn {
+    fn from_offset(source: &str, offset: usize, encoding: Encoding) -> Self {
+        let before = &source[..offset];
+        let line_start = before.rfind('\n').map_or(0, |newline| newline + 1);
+        let line_prefix = &before[line_start..];
+
+        let line = before.matches('\n').count();
+        let column = match encoding {
+            Encoding::Utf8 => line_prefix.len(),
+            Encoding::Utf16 => line_prefix.encode_utf16().count(),
+        };
+
+        Self { line, column }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::Scanner;
+
+    #[test]
+    fn test_span_line_columns_in_each_encoding() {
+        let source_code = "print \"😀\";\nprint \"ç\" + x;";
+        let tokens = Scanner::new(source_code).try_scan_all().unwrap();
+
+        let x = tokens[6].span;
+        assert_eq!(&source_code[x.byte_range()], "x");
+        assert_eq!((x.start.line, x.start.column), (2, 13));
+
+        let (start, end) = x.line_columns(source_code, Encoding::Utf8);
+        assert_eq!((start.line, start.column, end.column), (1, 13, 14));
+
+        let semicolon = tokens[2].span;
+        let (start, _) = semicolon.line_columns(source_code, Encoding::Utf8);
+        assert_eq!(start.column, 12);
+        let (start, _) = semicolon.line_columns(source_code, Encoding::Utf16);
+        assert_eq!(start.column, 10);
+    }
+
+    #[test]
+    fn test_span_line_columns_start_at_zero() {
+        let source_code = "print\n  x;";
+        let tokens = Scanner::new(source_code).try_scan_all().unwrap();
+
+        let (start, end) = tokens[0].span.line_columns(source_code, Encoding::Utf16);
+        assert_eq!((start.line, start.column), (0, 0));
+        assert_eq!((end.line, end.column), (0, 5));
+
+        let (start, _) = tokens[1].span.line_columns(source_code, Encoding::Utf16);
+        assert_eq!((start.line, start.column), (1, 2));
+    }
+}